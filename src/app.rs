@@ -1,298 +1,3934 @@
-use chrono::{DateTime, Duration, Local};
-use clap::Parser;
-use rand::{thread_rng, Rng};
-use rodio::{OutputStream, Sink, Source};
+use crate::config::{Config, Preferences, QuietHours, TimerMode, TimerSettings};
+use crate::digits::Font;
+use crate::event::TICK_RATE;
+use crate::handler::{key_hints, HintKey};
+use crate::keys::KeyMap;
+use crate::schedule;
+use crate::speech;
+use chrono::{DateTime, Duration, Local, LocalResult, NaiveTime, Offset, TimeZone};
+use clap::{Parser, Subcommand, ValueEnum};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use rodio::{decoder::DecoderError, source::SineWave, OutputStream, Sink, Source};
 use std::{
+    any::Any,
+    borrow::Cow,
+    cmp::Ordering,
+    collections::HashMap,
+    env,
     error,
-    fs::File,
-    io::BufReader,
-    sync::mpsc::{Sender, TryRecvError},
-    thread,
+    fs,
+    io::Cursor,
+    iter,
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Instant,
 };
 use tui::{
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     terminal::Frame,
-    widgets::{Block, Borders, Paragraph},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
 pub type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
 
+const FADE_IN: std::time::Duration = std::time::Duration::from_millis(500);
+/// Ticks the screen flashes for when the timer goes off
+const FLASH_TICKS: u8 = 8;
+/// Ticks the configured duration is shown over the clock after launch
+const INTRO_TICKS: u8 = 8;
+/// Levels of the `--visualizer` meter, lowest first, and how many bars it has
+const METER_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const METER_BARS: usize = 16;
+/// Ticks each label stays up before the next takes its turn
+const LABEL_TICKS: usize = 8;
+/// Below this size the clock is drawn on its own, without the label and queue regions
+const COMPACT_HEIGHT: u16 = 3;
+const COMPACT_WIDTH: u16 = 12;
+/// The key footer is only drawn with room for it on top of the scrollback layout
+const FOOTER_MIN_HEIGHT: u16 = 6;
+/// How long the overlay for an added or subtracted amount of time stays up
+const ADJUSTMENT_DISPLAY: std::time::Duration = std::time::Duration::from_millis(1500);
+/// Seconds of the countdown the debug key leaves
+const SKIP_TO_END_SECONDS: i64 = 10;
+/// How much of a sound plays for a chime
+const CHIME_LENGTH: std::time::Duration = std::time::Duration::from_millis(1000);
+/// How much of the `--tick-sound` file plays for each tick, and how long the built-in tick is
+const TICK_SOUND_LENGTH: std::time::Duration = std::time::Duration::from_millis(200);
+const BUILT_IN_TICK_LENGTH: std::time::Duration = std::time::Duration::from_millis(15);
+/// How long the dismiss key has to be held with `--hold-to-dismiss`
+const HOLD_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+/// The longest gap between key repeats that still counts as holding the key, which has to cover
+/// the delay before the terminal starts repeating
+const HOLD_GAP: std::time::Duration = std::time::Duration::from_millis(600);
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
+#[allow(clippy::partial_pub_fields)]
 pub struct Args {
-    /// Timer duration in format hh:mm:ss
+    #[command(flatten)]
+    pub countdown: CountdownArgs,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Timers set up by a mode rather than given with -t
+    #[arg(skip)]
+    queue: Vec<Timer>,
+
+    /// Set by the stopwatch mode, which has no use for a sound unless given a target
+    #[arg(skip)]
+    stopwatch: bool,
+}
+
+// What the plain timer counts down from, which the other modes set up their own way. Not a doc
+// comment, as clap would take it for the about text of every command flattening this
+#[derive(clap::Args, Default)]
+#[allow(clippy::partial_pub_fields)]
+pub struct CountdownArgs {
+    /// Timer duration in format hh:mm:ss, repeat to queue several timers in sequence
     #[arg(short, value_parser = parse_duration)]
-    time: Duration,
+    time: Vec<Duration>,
+
+    /// Run a share of this duration set by --percent, e.g. --of 20m --percent 75 for 15 minutes
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "percent", conflicts_with_all = ["time", "countup_target", "schedule", "presets"])]
+    of: Option<Duration>,
+
+    /// How much of the --of duration to run, from 1 to 1000 percent
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=1000), requires = "of")]
+    percent: Option<u16>,
+
+    /// Count down to the next time the clock reads this time of day, in format hh:mm, e.g.
+    /// 00:30 after midnight. Daylight saving changes are taken into account
+    #[arg(long, value_parser = parse_time_of_day, conflicts_with_all = ["time", "countup_target", "schedule", "presets", "of", "start_at", "start_in"])]
+    until: Option<NaiveTime>,
+
+    /// Which way the clock counts. Counting up goes off once the -t duration has passed and
+    /// carries on, or runs as a plain stopwatch without -t
+    #[arg(long, value_enum, value_name = "DIRECTION", conflicts_with_all = ["countup_target", "schedule", "presets", "until"])]
+    count: Option<CountDirection>,
+
+    /// Count up from zero like a stopwatch instead of down, sounding the alarm once this much
+    /// time has passed and carrying on counting
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, conflicts_with_all = ["time", "schedule"])]
+    countup_target: Option<Duration>,
+
+    /// Run the timers listed in this file in sequence, one per line as duration, label, sound
+    #[arg(long, value_name = "FILE", conflicts_with = "time")]
+    schedule: Option<PathBuf>,
+
+    /// Start in standby with a menu of durations to pick from, arming the chosen one with Enter.
+    /// Can be given more than once
+    #[arg(long = "preset", value_name = "DURATION", value_parser = parse_duration, conflicts_with_all = ["time", "countup_target", "schedule", "start_at", "start_in"])]
+    presets: Vec<Duration>,
+
+    /// Run the timer this many times in a row, starting the next session each time the alarm
+    /// is dismissed and exiting after the last
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["presets", "schedule", "countup_target"])]
+    sessions: Option<u32>,
+
+    /// Add time, like 5m or -1m, to the timer running with --session and exit
+    #[arg(long, value_parser = parse_duration, allow_hyphen_values = true, conflicts_with_all = ["time", "sound"])]
+    pub add: Option<Duration>,
+}
+
+// Options for the sound, the display, keys and everything else that works the same in every
+// mode
+#[derive(clap::Args)]
+#[allow(clippy::partial_pub_fields)]
+pub struct CommonArgs {
+    /// Path to the sound file to use, repeat to give each queued timer its own sound in order.
+    /// Timers without one use the first
+    #[arg(short)]
+    sound: Vec<String>,
+
+    /// An optional label for when the timer goes off, repeat to show several in turn
+    #[arg(short)]
+    pub label: Vec<String>,
+
+    /// A message to show from this far into the countdown on, e.g. "5m:Main set". Repeat for
+    /// each stage of the session
+    #[arg(long, value_name = "TIME:LABEL", value_parser = parse_stage)]
+    stage: Vec<(Duration, String)>,
+
+    /// Draw the timer inline instead of on the alternate screen, keeping it in the scrollback
+    #[arg(long)]
+    pub no_alternate_screen: bool,
+
+    /// Print the remaining time on a single updating line instead of drawing the TUI
+    #[arg(long, conflicts_with = "no_alternate_screen")]
+    pub inline: bool,
+
+    /// Print the timer status as JSON lines on stdout instead of drawing the TUI
+    #[arg(long, conflicts_with_all = ["no_alternate_screen", "inline"])]
+    pub json: bool,
+
+    /// Announce changes like starting, pausing and finishing as plain lines of text for screen
+    /// readers, instead of drawing the TUI
+    #[arg(long, conflicts_with_all = ["no_alternate_screen", "inline", "json"])]
+    pub accessible: bool,
+
+    /// Path to a config file, defaults to cli-timer/config.toml in the user config directory
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// How much detail to write to the log file: off, error, warn, info, debug or trace
+    #[arg(long, value_name = "LEVEL", default_value = "warn")]
+    pub log_level: log::LevelFilter,
+
+    /// Where to write the log, defaults to cli-timer/cli-timer.log in the user cache directory
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Use the timer settings from a `[profile.NAME]` section of the config file
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Seconds of silence between plays of the sound, instead of looping it continuously
+    #[arg(long, value_name = "SECONDS")]
+    repeat_interval: Option<u64>,
+
+    /// Number of times to play the sound before going quiet
+    #[arg(long, value_name = "COUNT")]
+    repeat: Option<u32>,
+
+    /// Exit with an error straight away if no sound device can be opened, rather than finding
+    /// out when the alarm is due
+    #[arg(long)]
+    require_audio: bool,
+
+    /// Read the sound file from disk again for every repeat of the alarm, so edits to it are
+    /// heard without restarting the timer
+    #[arg(long)]
+    repeat_from_file: bool,
+
+    /// Play the sound through once and then stay on the alarm screen in silence
+    #[arg(long, conflicts_with_all = ["repeat", "repeat_interval"])]
+    once: bool,
+
+    /// Longest timer duration accepted, to catch typos in -t
+    #[arg(long, value_parser = parse_duration, default_value = "720:00:00")]
+    max_duration: Duration,
+
+    /// Wait until this time of day, in format hh:mm, before starting the countdown
+    #[arg(long, value_parser = parse_time_of_day, conflicts_with = "start_in")]
+    start_at: Option<NaiveTime>,
+
+    /// Wait this long, in format hh:mm:ss, before starting the countdown
+    #[arg(long, value_parser = parse_duration)]
+    start_in: Option<Duration>,
+
+    /// Alarm volume as a percentage
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    volume: Option<u8>,
+
+    /// Save volume and mute changes made while running back to the config file on exit
+    #[arg(long)]
+    pub remember: bool,
+
+    /// Don't flash the screen and ring the terminal bell when the timer goes off
+    #[arg(long)]
+    no_flash: bool,
+
+    /// Start the alarm at full volume instead of fading it in, for short sounds the fade would clip
+    #[arg(long)]
+    no_fade: bool,
+
+    /// Start the queue of timers over from the first once the last one is dismissed, forever
+    #[arg(long)]
+    repeat_whole_session: bool,
+
+    /// Colour of the clock, either a name like light-blue or a 256-colour palette index from 0
+    /// to 255. A random colour is picked otherwise
+    #[arg(long, value_name = "COLOUR", value_parser = parse_colour)]
+    color: Option<Color>,
+
+    /// Draw everything in the terminal's default colours, also enabled by setting NO_COLOR
+    #[arg(long)]
+    no_color: bool,
+
+    /// Show the current time above the countdown
+    #[arg(long)]
+    clock: bool,
+
+    /// Let the alarm play for this long, 10 seconds if no duration is given, then dismiss it
+    /// without a key press, exiting after the last timer
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, num_args = 0..=1, default_missing_value = "10s")]
+    auto_exit: Option<Duration>,
+
+    /// How long the alarm sound plays under --auto-exit, 2 seconds if no duration is given. The
+    /// screen stays on the alarm in silence until --auto-exit moves on
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, num_args = 0..=1, default_missing_value = "2s", requires = "auto_exit")]
+    alarm_duration: Option<Duration>,
+
+    /// Enable the t key, which skips to the last 10 seconds to try out the alarm
+    #[arg(long)]
+    debug_keys: bool,
+
+    /// Scroll labels too long for the screen instead of cutting them off
+    #[arg(long)]
+    marquee: bool,
+
+    /// Start the alarm quietly and turn it up every --escalate-interval seconds until dismissed
+    #[arg(long)]
+    escalate: bool,
+
+    /// Percentage the alarm volume goes up by with each escalation
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(1..=100), requires = "escalate")]
+    escalate_step: u8,
+
+    /// Seconds between escalations
+    #[arg(long, default_value_t = 10, requires = "escalate")]
+    escalate_interval: u64,
+
+    /// Switch to this sound once the escalating alarm reaches full volume
+    #[arg(long, value_name = "PATH", requires = "escalate")]
+    escalate_sound: Option<String>,
+
+    /// Play a short snippet of the alarm once half the time has passed
+    #[arg(long)]
+    halfway_chime: bool,
+
+    /// Play the start of a sound when the countdown begins, the alarm sound if no path is given
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    sound_start: Option<Option<String>>,
+
+    /// How much time the extend key gives a timer that has gone off
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, default_value = "5m")]
+    extend: Duration,
+
+    /// Hide the line along the bottom listing the keys that do something right now
+    #[arg(long)]
+    no_footer: bool,
+
+    /// Don't show the duration that was set, e.g. "5:00 timer started", for the first couple
+    /// of seconds
+    #[arg(long)]
+    no_intro: bool,
+
+    /// Ring the terminal bell in this rhythm while the alarm goes off, one character per tick
+    /// where . rings and - rests, e.g. "...---..."
+    #[arg(long, value_name = "PATTERN", value_parser = parse_bell_pattern)]
+    bell_pattern: Option<BellPattern>,
+
+    /// Only play the alarm every nth time a timer goes off, e.g. every 4th interval with a
+    /// queue or a restarted timer. The other times only flash the screen
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    sound_every: Option<u32>,
+
+    /// Show the time left as a row of this many blocks below the clock, which empties from the
+    /// left as time passes
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    blocks: Option<u16>,
+
+    /// Whether the `--blocks` row drains away as time passes or fills up instead
+    #[arg(long, value_enum, default_value_t = ProgressDirection::Drain, requires = "blocks")]
+    progress_direction: ProgressDirection,
+
+    /// Only dismiss the alarm once the pause key has been held down for a couple of seconds
+    #[arg(long)]
+    hold_to_dismiss: bool,
+
+    /// When the alarm is unmuted, play it again from the start rather than carrying on from
+    /// where it was muted
+    #[arg(long)]
+    restart_sound_on_resume: bool,
+
+    /// Go off with the screen alone, never making a sound even with -s given. Chimes, ticks
+    /// and terminal bells are kept quiet too
+    #[arg(long)]
+    quiet_trigger: bool,
+
+    /// Read the label aloud when the timer goes off, with say on macOS or spd-say or espeak on
+    /// Linux. The sound file is optional with this
+    #[arg(long)]
+    pub speak: bool,
+
+    /// Colour to draw the clock in once less than `--warn-threshold` is left, e.g. red or
+    /// light-yellow
+    #[arg(long, value_name = "COLOUR", value_parser = parse_colour, requires = "warn_threshold")]
+    warn_color: Option<Color>,
+
+    /// How much time left switches the clock to `--warn-color`, in seconds or as a duration
+    /// like 1m
+    #[arg(long, value_name = "SECONDS", value_parser = parse_duration, requires = "warn_color")]
+    warn_threshold: Option<Duration>,
+
+    /// Stop the system sleeping until the timer exits, so the alarm still goes off on a laptop
+    /// left alone. Needs systemd-inhibit on Linux or caffeinate on macOS
+    #[arg(long)]
+    pub keep_awake: bool,
+
+    /// Milliseconds to hold the full duration on screen before the countdown starts
+    #[arg(long, value_name = "MS")]
+    pub grace: Option<u64>,
+
+    /// Redraw at most this many times a second to save CPU, skipping ticks where nothing on
+    /// screen changed. The screen updates every 250 ms, so only values below 4 slow it down
+    #[arg(long, value_name = "FPS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_fps: Option<u32>,
+
+    /// Seed for the random colours, to get the same ones as an earlier run. The seed used is
+    /// printed on exit with the summary and shown on the info screen
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Show a pulsing meter under the clock while the alarm plays
+    #[arg(long)]
+    visualizer: bool,
+
+    /// Shade a block across the screen behind the clock that shrinks with the time left, and
+    /// flashes once the timer goes off
+    #[arg(long)]
+    bar_fill: bool,
+
+    /// Leave the clock at zero once the timer goes off instead of counting the time since
+    #[arg(long)]
+    freeze_on_trigger: bool,
+
+    /// Show roughly how long is left under the clock, like "about 5 minutes left"
+    #[arg(long)]
+    human: bool,
+
+    /// How to write the time on the clock: hms, ms, compact, or a template such as "%M:%S"
+    /// using %H, %M and %S for padded fields and %h, %m and %s for totals. An unusable
+    /// template falls back to hms
+    #[arg(long, value_name = "FORMAT")]
+    duration_format: Option<String>,
+
+    /// Fire the alarm on the exact millisecond instead of on the next tick, which can be up
+    /// to a quarter of a second late
+    #[arg(long)]
+    precise: bool,
+
+    /// Beep each time this much less is left, so progress can be followed without looking,
+    /// e.g. 1m
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    beep_every: Option<Duration>,
+
+    /// Whether `--beep-every` beeps once per interval still to go or just once
+    #[arg(long, value_enum, default_value_t = BeepPattern::Count, requires = "beep_every")]
+    beep_pattern: BeepPattern,
+
+    /// Play a soft tick every second while the timer counts down
+    #[arg(long)]
+    ticking: bool,
+
+    /// Sound file to use for `--ticking` instead of the built-in tick
+    #[arg(long, value_name = "PATH", requires = "ticking")]
+    tick_sound: Option<String>,
+
+    /// A softer sound to play when the timer first goes off, before switching to the usual
+    /// alarm if it hasn't been dismissed after `--gentle-for`
+    #[arg(long, value_name = "PATH")]
+    gentle_sound: Option<String>,
+
+    /// How long the gentle sound plays before the usual alarm takes over
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, default_value = "30s", requires = "gentle_sound")]
+    gentle_for: Duration,
+
+    /// Seconds into the sound file to start playing the alarm from, to skip a quiet intro
+    #[arg(long, value_name = "SECONDS")]
+    sound_start_offset: Option<u64>,
+
+    /// Write the duration, sound, label, colour, volume and mode of this run to a config file
+    /// that `--config` can load to run it again. Only a single timer can be saved, not a queue,
+    /// schedule, sessions or a pomodoro
+    #[arg(long, value_name = "FILE")]
+    pub save_config: Option<PathBuf>,
+
+    /// Don't print a line saying which timer finished, or what a pomodoro session got done, once
+    /// the timer exits
+    #[arg(long)]
+    pub no_summary: bool,
+
+    /// Draw the clock in large characters using this font
+    #[arg(long, value_enum)]
+    font: Option<Font>,
+
+    /// Run the timer in a new tmux window named after its label and return straight away, or
+    /// run it here if not inside tmux
+    #[arg(long)]
+    pub detach: bool,
+
+    /// Run this command through the shell once the timer exits, e.g. to lock the screen
+    #[arg(long, value_name = "COMMAND")]
+    pub exec: Option<String>,
+
+    /// Whether --exec runs only once the last timer has gone off, or whenever the timer exits
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "any", requires = "exec")]
+    pub exec_on: ExecOn,
+
+    /// Show the available fonts and exit
+    #[arg(long, exclusive = true)]
+    pub list_fonts: bool,
+
+    /// Serve the timer status as JSON to clients connecting to a Unix socket at this path
+    #[arg(long)]
+    pub socket: Option<PathBuf>,
+
+    /// Write the seconds left to a named pipe at this path every tick, for status bars. The pipe
+    /// is created if needed and removed on exit
+    #[arg(long)]
+    pub fifo: Option<PathBuf>,
+
+    /// Write the process ID to this file while the timer runs, for scripts sending SIGUSR1 to
+    /// pause it. The file is removed on exit
+    #[arg(long, value_name = "PATH")]
+    pub pid_file: Option<PathBuf>,
+
+    /// Ask for confirmation before quitting a running or paused timer
+    #[arg(long)]
+    confirm_quit: bool,
+
+    /// Share the timer through a session file, defaulting to one in the user cache directory
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub session: Option<Option<PathBuf>>,
+}
+
+// Runs a plain timer from the flattened `Args` unless another mode is picked
+#[derive(Parser)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    mode: Option<Mode>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand)]
+pub enum Mode {
+    /// Count down from each -t duration, the same as giving no mode
+    Timer(Args),
+    /// Count up from zero, sounding the alarm only once --countup-target is reached
+    Stopwatch(StopwatchArgs),
+    /// Alternate work and short breaks, with a long break after the last round, and repeat
+    Pomodoro(PomodoroArgs),
+}
+
+#[derive(clap::Args)]
+pub struct StopwatchArgs {
+    /// Sound the alarm once this much time has passed, carrying on counting
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    countup_target: Option<Duration>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(clap::Args)]
+pub struct PomodoroArgs {
+    /// Length of each work session
+    #[arg(long, value_parser = parse_duration, default_value = "25m")]
+    work: Duration,
+
+    /// Length of the break after each work session
+    #[arg(long, value_parser = parse_duration, default_value = "5m")]
+    short_break: Duration,
+
+    /// Length of the break after the last round
+    #[arg(long, value_parser = parse_duration, default_value = "15m")]
+    long_break: Duration,
+
+    /// Work sessions before the long break
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=100))]
+    rounds: u32,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+impl Args {
+    /// The part of the setup `--save-config` has nowhere to write, which would otherwise come
+    /// back as a single plain timer
+    fn unsavable(&self) -> Option<&'static str> {
+        let countdown = &self.countdown;
+        [
+            (!self.queue.is_empty(), "a pomodoro"),
+            (countdown.time.len() > 1, "more than one -t"),
+            (countdown.schedule.is_some(), "--schedule"),
+            (countdown.sessions.is_some(), "--sessions"),
+            (countdown.until.is_some(), "--until"),
+            (!countdown.presets.is_empty(), "--preset"),
+            (self.common.repeat_whole_session, "--repeat-whole-session"),
+        ]
+        .into_iter()
+        .find_map(|(unsavable, setup)| unsavable.then_some(setup))
+    }
+}
+
+impl Cli {
+    /// Turns the chosen mode into the options for the timer to run
+    pub fn into_args(self) -> Result<Args> {
+        let mut args = self.mode_args()?;
+
+        if let (Some(reference), Some(percent)) = (args.countdown.of, args.countdown.percent) {
+            args.countdown.time.push(percent_of(reference, percent)?);
+        }
+
+        if args.countdown.count == Some(CountDirection::Up) {
+            if args.countdown.time.len() > 1 {
+                return Err("--count up takes a single -t to count up to".into());
+            }
+
+            // Counting up with nothing to count to is a stopwatch, which needs no sound
+            args.countdown.countup_target = args.countdown.time.pop();
+            if args.countdown.countup_target.is_none() {
+                args.countdown.countup_target = Some(args.common.max_duration);
+                args.stopwatch = true;
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// The arguments with whatever the chosen mode sets up filled in
+    fn mode_args(self) -> Result<Args> {
+        match self.mode {
+            None => Ok(self.args),
+            Some(Mode::Timer(args)) => Ok(args),
+            Some(Mode::Stopwatch(stopwatch)) => Ok(Args {
+                countdown: CountdownArgs {
+                    countup_target: Some(
+                        stopwatch.countup_target.unwrap_or(stopwatch.common.max_duration),
+                    ),
+                    ..CountdownArgs::default()
+                },
+                common: stopwatch.common,
+                queue: Vec::new(),
+                stopwatch: true,
+            }),
+            Some(Mode::Pomodoro(pomodoro)) => {
+                let mut args = Args {
+                    countdown: CountdownArgs::default(),
+                    common: pomodoro.common,
+                    queue: Vec::new(),
+                    stopwatch: false,
+                };
+
+                let timer = |duration, label: &str, is_break| Timer {
+                    duration,
+                    label: Some(String::from(label)),
+                    sound: None,
+                    is_break,
+                };
+                for round in 1..=pomodoro.rounds {
+                    args.queue.push(timer(pomodoro.work, "Work", false));
+                    if round < pomodoro.rounds {
+                        args.queue.push(timer(pomodoro.short_break, "Short break", true));
+                    }
+                }
+                args.queue.push(timer(pomodoro.long_break, "Long break", true));
+                args.common.repeat_whole_session = true;
+                Ok(args)
+            }
+        }
+    }
+}
+
+/// `percent` percent of `reference`, to the nearest second
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+fn percent_of(reference: Duration, percent: u16) -> Result<Duration> {
+    if reference <= Duration::zero() {
+        return Err("--of must be longer than zero".into());
+    }
+
+    let seconds = reference.num_seconds() * i64::from(percent);
+    let seconds = (seconds + 50) / 100;
+    if seconds == 0 {
+        return Err(format!("{percent}% of {} is less than a second", format_hms(reference)).into());
+    }
+
+    Ok(Duration::seconds(seconds))
+}
+
+/// Parses a duration either in format hh:mm:ss or with units like 1h30m, 5m or 90s
+pub fn parse_duration(arg: &str) -> std::result::Result<Duration, String> {
+    let time_in_seconds = if arg.ends_with(['h', 'm', 's']) {
+        parse_unit_seconds(arg)?
+    } else {
+        parse_clock_seconds(arg)?
+    };
+
+    time_in_seconds
+        .checked_mul(1000)
+        .map(Duration::milliseconds)
+        .ok_or_else(|| format!("duration {arg} is too large"))
+}
+
+fn parse_clock_seconds(arg: &str) -> std::result::Result<i64, String> {
+    let split_time_string: Vec<&str> = arg.split(':').collect();
+
+    let mut time_in_seconds: i64 = 0;
+    for (i, num_string) in split_time_string.iter().enumerate() {
+        let modifier = match i {
+            0 => 60 * 60, // hours
+            1 => 60,      // minutes
+            2 => 1,       // seconds
+            _ => 1,
+        };
+
+        let in_seconds = num_string.parse::<i64>().map_err(|e| e.to_string())?;
+        time_in_seconds = in_seconds
+            .checked_mul(modifier)
+            .and_then(|seconds| time_in_seconds.checked_add(seconds))
+            .ok_or_else(|| format!("duration {arg} is too large"))?;
+    }
+
+    Ok(time_in_seconds)
+}
+
+fn parse_unit_seconds(arg: &str) -> std::result::Result<i64, String> {
+    let (negative, units) = match arg.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, arg.strip_prefix('+').unwrap_or(arg)),
+    };
+
+    let mut time_in_seconds: i64 = 0;
+    let mut number = String::new();
+    for c in units.chars() {
+        let modifier = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(format!("unknown unit '{c}' in {arg}")),
+        };
+
+        let in_seconds = number
+            .parse::<i64>()
+            .map_err(|_| format!("missing number before '{c}' in {arg}"))?;
+        time_in_seconds = in_seconds
+            .checked_mul(modifier)
+            .and_then(|seconds| time_in_seconds.checked_add(seconds))
+            .ok_or_else(|| format!("duration {arg} is too large"))?;
+        number.clear();
+    }
+
+    Ok(if negative {
+        -time_in_seconds
+    } else {
+        time_in_seconds
+    })
+}
+
+/// Which way the clock counts, for `--count`
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum CountDirection {
+    /// From zero up to the target
+    Up,
+    /// From the duration down to zero
+    Down,
+}
+
+/// When `--exec` runs its command
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum ExecOn {
+    /// Only if the last timer went off, not if it was quit before then
+    Success,
+    /// Whenever the timer exits
+    Any,
+}
+
+/// Which way the `--blocks` row moves as time passes
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum ProgressDirection {
+    /// Filled with the time elapsed, starting empty
+    Fill,
+    /// Filled with the time left, emptying from the left
+    Drain,
+}
+
+/// How the time on the clock is written, for `--duration-format`
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum DurationFormat {
+    /// hh:mm:ss
+    #[default]
+    Hms,
+    /// mm:ss, with the minutes running past 59
+    Ms,
+    /// hh:mm:ss without the leading fields that are zero, e.g. 5:00
+    Compact,
+    /// A template where %H, %M and %S are the zero-padded hours, minutes and seconds, %h, %m
+    /// and %s are the totals in each unit, and %% is a percent sign
+    Custom(String),
+}
+
+impl DurationFormat {
+    /// Reads `hms`, `ms`, `compact` or a custom template
+    pub fn parse(format: &str) -> std::result::Result<Self, String> {
+        match format {
+            "hms" => Ok(Self::Hms),
+            "ms" => Ok(Self::Ms),
+            "compact" => Ok(Self::Compact),
+            template if template.contains('%') => {
+                expand_template(template, Duration::zero(), 2)?;
+                Ok(Self::Custom(template.to_owned()))
+            }
+            other => Err(format!(
+                "unknown duration format {other:?}, expected hms, ms, compact or a template like %M:%S"
+            )),
+        }
+    }
+}
+
+/// Fills the fields of a `--duration-format` template in with `duration`
+#[allow(clippy::arithmetic_side_effects)]
+fn expand_template(
+    template: &str,
+    duration: Duration,
+    hours_width: usize,
+) -> std::result::Result<String, String> {
+    let mut text = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+
+        let field = match chars.next() {
+            Some('H') => format!("{:0>hours_width$}", duration.num_hours().abs()),
+            Some('M') => format!("{:0>2}", duration.num_minutes().abs() % 60),
+            Some('S') => format!("{:0>2}", duration.num_seconds().abs() % 60),
+            Some('h') => duration.num_hours().abs().to_string(),
+            Some('m') => duration.num_minutes().abs().to_string(),
+            Some('s') => duration.num_seconds().abs().to_string(),
+            Some('%') => "%".to_owned(),
+            Some(other) => return Err(format!("unknown field %{other} in duration format")),
+            None => return Err("duration format ends in a lone %".to_owned()),
+        };
+        text.push_str(&field);
+    }
+
+    Ok(text)
+}
+
+/// How many times `--beep-every` beeps as each interval passes
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum BeepPattern {
+    /// Once for every interval left, so three beeps with three minutes to go
+    Count,
+    /// A single beep
+    Once,
+}
+
+/// A rhythm for the terminal bell, holding whether it rings on each tick
+#[derive(Clone, Debug)]
+pub struct BellPattern(pub Vec<bool>);
+
+impl BellPattern {
+    /// Whether the bell rings on this tick, repeating the pattern
+    #[must_use]
+    #[allow(clippy::modulo_arithmetic)]
+    pub fn rings_on(&self, tick: usize) -> bool {
+        self.0.get(tick % self.0.len().max(1)) == Some(&true)
+    }
+}
+
+/// Splits a `--stage` into the time it starts and its label, taking the longest time before a
+/// colon so labels can have colons of their own
+fn parse_stage(arg: &str) -> std::result::Result<(Duration, String), String> {
+    arg.match_indices(':')
+        .rev()
+        .find_map(|(i, _)| {
+            let at = parse_duration(&arg[..i]).ok()?;
+            Some((at, arg[i + 1..].to_owned()))
+        })
+        .ok_or_else(|| format!("expected TIME:LABEL, e.g. 5m:Main set, not {arg}"))
+}
+
+fn parse_bell_pattern(arg: &str) -> std::result::Result<BellPattern, String> {
+    if arg.is_empty() {
+        return Err(String::from("pattern is empty"));
+    }
+
+    arg.chars()
+        .map(|c| match c {
+            '.' => Ok(true),
+            '-' => Ok(false),
+            other => Err(format!("unexpected {other:?}, use . to ring and - to rest")),
+        })
+        .collect::<std::result::Result<_, _>>()
+        .map(BellPattern)
+}
+
+/// The named terminal colours that can be given on the command line or in the config
+const COLOURS: [(&str, Color); 16] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("gray", Color::Gray),
+    ("dark-gray", Color::DarkGray),
+    ("light-red", Color::LightRed),
+    ("light-green", Color::LightGreen),
+    ("light-yellow", Color::LightYellow),
+    ("light-blue", Color::LightBlue),
+    ("light-magenta", Color::LightMagenta),
+    ("light-cyan", Color::LightCyan),
+    ("white", Color::White),
+];
+
+/// Parses one of the named terminal colours, e.g. red, light-blue or dark-gray, or an index
+/// into the 256-colour palette
+fn parse_colour(arg: &str) -> std::result::Result<Color, String> {
+    if arg.bytes().all(|byte| byte.is_ascii_digit()) {
+        return arg
+            .parse()
+            .map(Color::Indexed)
+            .map_err(|_| format!("colour index {arg} is out of range, it must be 0 to 255"));
+    }
+
+    let simplify = |name: &str| {
+        name.to_ascii_lowercase()
+            .replace(['-', '_'], "")
+            .replace("grey", "gray")
+    };
+
+    COLOURS
+        .iter()
+        .find(|&&(name, _)| simplify(name) == simplify(arg))
+        .map(|&(_, colour)| colour)
+        .ok_or_else(|| format!("unknown colour {arg}"))
+}
+
+/// The name or index `parse_colour` takes for a colour
+fn colour_name(colour: Color) -> Option<String> {
+    if let Color::Indexed(index) = colour {
+        return Some(index.to_string());
+    }
+
+    COLOURS
+        .iter()
+        .find(|&&(_, named)| named == colour)
+        .map(|&(name, _)| String::from(name))
+}
+
+/// Expands a leading `~` and any `$VAR` or `${VAR}` in a path the way a shell would, for paths
+/// from config files and quoted arguments that never went through one
+pub fn expand_path(path: &str) -> Result<String> {
+    let lookup = |name: &str| {
+        env::var(name).map_err(|_| format!("{path} uses ${name}, which isn't set"))
+    };
+
+    let mut expanded = String::new();
+    let mut rest = path;
+    if rest == "~" || rest.starts_with("~/") {
+        let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+        expanded.push_str(&lookup(home)?);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced.find('}').ok_or_else(|| format!("{path} has an unclosed ${{"))?;
+                (&braced[..end], &braced[end + 1..])
+            }
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+        } else {
+            expanded.push_str(&lookup(name)?);
+        }
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// Expands a sound path with [`expand_path`], failing if what it expands to doesn't exist
+pub fn expand_sound(sound: &str) -> Result<String> {
+    let expanded = expand_path(sound).map_err(|e| format!("bad sound path: {e}"))?;
+    if expanded != sound && !Path::new(&expanded).exists() {
+        return Err(format!("sound file {sound} expands to {expanded}, which doesn't exist").into());
+    }
+
+    Ok(expanded)
+}
+
+fn parse_time_of_day(arg: &str) -> std::result::Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(arg, "%H:%M:%S").or_else(|_| NaiveTime::parse_from_str(arg, "%H:%M"))
+}
+
+/// Whether the terminal can show colours at all, going by `TERM`. Windows terminals usually
+/// leave it unset but handle colour fine
+fn colour_supported() -> bool {
+    match env::var("TERM") {
+        Ok(term) => !term.is_empty() && term != "dumb",
+        Err(_) => cfg!(windows),
+    }
+}
+
+/// The next moment after `now` that the clock in its time zone reads `time`, today or
+/// tomorrow. A time repeated when the clocks go back is taken at whichever reading comes next,
+/// and one skipped when they go forward falls the length of the gap later, as if the clocks
+/// hadn't changed
+#[allow(clippy::arithmetic_side_effects)]
+pub fn next_occurrence<Tz: TimeZone>(time: NaiveTime, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let zone = now.timezone();
+    let today = now.date_naive();
+
+    for date in [today, today.succ_opt()?] {
+        let local = date.and_time(time);
+        let readings = match zone.from_local_datetime(&local) {
+            LocalResult::Single(reading) => vec![reading],
+            LocalResult::Ambiguous(first, second) => vec![first, second],
+            LocalResult::None => {
+                // Measure from the offset before the gap, a day earlier being well clear of it
+                let before = zone
+                    .offset_from_local_datetime(&(local - Duration::days(1)))
+                    .earliest()?
+                    .fix();
+                let utc = local - Duration::seconds(i64::from(before.local_minus_utc()));
+                vec![zone.from_utc_datetime(&utc)]
+            }
+        };
+
+        if let Some(reading) = readings.into_iter().find(|reading| reading > now) {
+            return Some(reading);
+        }
+    }
+
+    None
+}
+
+/// How an ignored alarm gets louder
+#[derive(Clone, Debug)]
+pub struct Escalation {
+    /// Percentage to start at and go up by each interval
+    pub step: u8,
+    pub interval: std::time::Duration,
+    /// A louder sound to switch to once the alarm reaches full volume
+    pub sound: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Timer {
+    pub duration: Duration,
+    pub label: Option<String>,
+    pub sound: Option<String>,
+    /// Whether this is a break between focus sessions rather than one itself
+    pub is_break: bool,
+}
+
+/// Formats a duration as hh:mm:ss, ignoring its sign
+fn format_hms(duration: Duration) -> String {
+    format_hms_padded(duration, 2)
+}
+
+/// Formats the time on the clock as a sign followed by the time in `format`, where the sign
+/// is "+" in overtime and a space otherwise. The hours are padded to at least `hours_width`
+/// digits, and never fewer than two, so the clock keeps the same width as it counts down
+#[must_use]
+#[allow(clippy::arithmetic_side_effects)]
+pub fn format_time_left(
+    duration: Duration,
+    overtime: bool,
+    hours_width: usize,
+    format: &DurationFormat,
+) -> String {
+    let sign = if overtime { '+' } else { ' ' };
+    let hours_width = hours_width.max(2);
+    let hours = duration.num_hours().abs();
+    let minutes = duration.num_minutes().abs() % 60;
+    let seconds = duration.num_seconds().abs() % 60;
+
+    let time = match format {
+        DurationFormat::Hms => format_hms_padded(duration, hours_width),
+        DurationFormat::Ms => format!("{:0>2}:{seconds:0>2}", duration.num_minutes().abs()),
+        DurationFormat::Compact if hours > 0 => format!("{hours}:{minutes:0>2}:{seconds:0>2}"),
+        DurationFormat::Compact if minutes > 0 => format!("{minutes}:{seconds:0>2}"),
+        DurationFormat::Compact => seconds.to_string(),
+        DurationFormat::Custom(template) => expand_template(template, duration, hours_width)
+            .unwrap_or_else(|_| format_hms_padded(duration, hours_width)),
+    };
+
+    format!("{sign}{time}")
+}
+
+/// Restyles everything already drawn in its area, leaving the text as it is
+struct Shade {
+    style: Style,
+}
+
+impl Widget for Shade {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+    }
+}
+
+/// Draws the lines in a bordered box in the middle of `area`, over whatever was there
+fn render_popup<B: Backend>(
+    frame: &mut Frame<'_, B>,
+    area: Rect,
+    lines: Vec<Spans<'_>>,
+    title: &str,
+    style: Style,
+) {
+    let height = u16::try_from(lines.len()).unwrap_or(u16::MAX).saturating_add(2);
+    let width = lines
+        .iter()
+        .map(Spans::width)
+        .max()
+        .and_then(|width| u16::try_from(width).ok())
+        .unwrap_or(u16::MAX)
+        .saturating_add(4);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(style)
+            .alignment(Alignment::Center),
+        popup,
+    );
+}
+
+/// Roughly how long is left, for `--human`. Under a minute reads "less than a minute left",
+/// under an hour rounds to the nearest minute and anything longer to the nearest hour, so 90
+/// seconds is "about 2 minutes left" and 59m30s is "about an hour left"
+#[must_use]
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+pub fn human_time_left(time_left: Duration, overtime: bool) -> String {
+    let seconds = time_left.num_seconds();
+    let minutes = (seconds + 30) / 60;
+    let hours = (seconds + 30 * 60) / (60 * 60);
+
+    if overtime {
+        String::from("overtime")
+    } else if seconds < 60 {
+        String::from("less than a minute left")
+    } else if minutes == 1 {
+        String::from("about a minute left")
+    } else if minutes < 60 {
+        format!("about {minutes} minutes left")
+    } else if hours == 1 {
+        String::from("about an hour left")
+    } else {
+        format!("about {hours} hours left")
+    }
+}
+
+/// Formats a duration as hh:mm:ss with the hours padded to at least `hours_width` digits,
+/// ignoring its sign
+#[allow(clippy::modulo_arithmetic)]
+fn format_hms_padded(duration: Duration, hours_width: usize) -> String {
+    let seconds = duration.num_seconds().abs() % 60;
+    let minutes = duration.num_minutes().abs() % 60;
+    let hours = duration.num_hours().abs();
+
+    format!("{hours:0>hours_width$}:{minutes:0>2}:{seconds:0>2}")
+}
+
+/// The window of `text` visible at `offset` as it scrolls through `width` columns, or all of
+/// it when it already fits
+#[allow(clippy::arithmetic_side_effects, clippy::modulo_arithmetic)]
+fn scroll(text: &str, width: usize, offset: usize) -> Cow<'_, str> {
+    let length = text.chars().count();
+    if length <= width {
+        return text.into();
+    }
+
+    let looped: Vec<char> = text.chars().chain("   ".chars()).collect();
+    looped
+        .iter()
+        .cycle()
+        .skip(offset % looped.len())
+        .take(width)
+        .collect::<String>()
+        .into()
+}
+
+/// Messages sent to the thread playing the alarm
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SoundCommand {
+    Stop,
+    Volume(f32),
+    Pause,
+    Resume,
+}
+
+/// Where the alarm thread sends the sound
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum AudioOutput {
+    /// The default sound device
+    #[default]
+    Device,
+    /// A sink nothing plays from, so the alarm thread can run without a sound card
+    Idle,
+}
+
+/// Makes sure the default sound device can be opened, for `--require-audio`
+fn check_audio() -> Result<()> {
+    let (_stream, handle) = OutputStream::try_default()
+        .map_err(|e| format!("no audio output available, and --require-audio is set: {e}"))?;
+    Sink::try_new(&handle)
+        .map_err(|e| format!("could not play audio, and --require-audio is set: {e}"))?;
+
+    Ok(())
+}
+
+/// Opens a sink on `output`, logging why when it can't be. The sink only plays for as long as
+/// the first half is kept alive
+fn open_sink(output: AudioOutput) -> Option<(Box<dyn Any>, Sink)> {
+    match output {
+        AudioOutput::Device => {
+            let (stream, handle) = OutputStream::try_default()
+                .map_err(|e| log::error!("could not open output stream: {e}"))
+                .ok()?;
+            let sink = Sink::try_new(&handle)
+                .map_err(|e| log::error!("could not create sink in sound thread: {e}"))
+                .ok()?;
+
+            Some((Box::new(stream), sink))
+        }
+        AudioOutput::Idle => {
+            let (sink, queue) = Sink::new_idle();
+            Some((Box::new(queue), sink))
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum State {
+    Waiting,
+    /// Showing the `--preset` menu until one is picked and armed
+    Standby,
+    Running,
+    Paused,
+    Triggered,
+    Restart,
+    Quitting,
+}
+
+pub struct App {
+    pub running: bool,
+    pub state: State,
+    pub pre_pause_state: Option<State>,
+    pub duration: Duration,
+    pub time_left: Duration,
+    pub end_time: DateTime<Local>,
+    pub colour: Color,
+    pub messages: Vec<String>,
+    /// Messages shown while the timer runs, from how far into it each one starts, in order
+    pub stages: Vec<(Duration, String)>,
+    pub label_ticks: usize,
+    pub sound_file: String,
+    pub sender: Option<Sender<SoundCommand>>,
+    /// The thread playing the alarm, which exits once it is told to stop
+    pub sound_thread: Option<JoinHandle<()>>,
+    pub audio_output: AudioOutput,
+    pub keys: KeyMap,
+    pub repeat: Option<u32>,
+    pub repeat_interval: Option<std::time::Duration>,
+    pub queue: Vec<Timer>,
+    pub queue_index: usize,
+    pub loop_queue: bool,
+    pub round: u32,
+    /// How many times `--sessions` runs the timer, and which run this is, counting from 1
+    pub sessions: Option<u32>,
+    pub session: u32,
+    pub error: Option<String>,
+    pub start_time: Option<DateTime<Local>>,
+    pub volume: u8,
+    pub muted: bool,
+    pub flash: bool,
+    pub flash_ticks: u8,
+    /// Ticks left of the overlay showing the duration that was set
+    pub intro_ticks: u8,
+    pub fade: bool,
+    pub last_adjustment: Option<(Duration, Instant)>,
+    pub font: Option<Font>,
+    pub confirm_quit: bool,
+    pub pre_quit_state: Option<State>,
+    pub finished: Option<Timer>,
+    /// Whether the last timer has gone off, rather than being quit or restarted before then
+    pub completed: bool,
+    pub plain: bool,
+    pub wall_clock: bool,
+    pub auto_exit: Option<Duration>,
+    /// How long the alarm sound plays before going quiet under `--auto-exit`
+    pub alarm_duration: Option<Duration>,
+    pub triggered_at: Option<DateTime<Local>>,
+    pub paused_total: Duration,
+    /// When the current pause began
+    pub paused_at: Option<DateTime<Local>>,
+    pub debug_keys: bool,
+    pub quiet_hours: Option<QuietHours>,
+    pub marquee: bool,
+    pub marquee_offset: usize,
+    pub count_up: bool,
+    pub escalation: Option<Escalation>,
+    pub escalation_level: Option<u8>,
+    pub halfway_chime: bool,
+    pub chimed: bool,
+    pub start_chime: Option<String>,
+    pub extend_by: Duration,
+    pub footer: bool,
+    pub bell_pattern: Option<BellPattern>,
+    pub bell_step: usize,
+    pub fixed_colour: bool,
+    /// Where the random colours come from, so `--seed` can pick the same ones again
+    pub seed: u64,
+    pub rng: StdRng,
+    pub sound_every: u32,
+    pub trigger_count: u32,
+    /// Whether the alarm that last went off is being kept silent, by `--sound-every` or by
+    /// quiet hours turned all the way down, so nothing later in it makes a sound either
+    pub alarm_silenced: bool,
+    /// How many times the terminal bell has rung
+    pub bells_rung: u32,
+    /// Focus sessions completed so far, and the time spent on them and on breaks, for the
+    /// summary printed after a looping session
+    pub focus_sessions: u32,
+    pub focus_time: Duration,
+    pub break_time: Duration,
+    pub blocks: Option<u16>,
+    pub hold_to_dismiss: bool,
+    /// When the current hold started and when the last repeat of the key arrived
+    pub hold: Option<(Instant, Instant)>,
+    pub restart_sound_on_resume: bool,
+    pub quiet_trigger: bool,
+    pub speak: bool,
+    pub show_info: bool,
+    /// Whether the d key has flipped the clock to show the other of time left and time elapsed
+    pub show_elapsed: bool,
+    /// A new duration being typed in, and why the last one entered was turned down
+    pub input: Option<String>,
+    pub input_error: Option<String>,
+    /// How much time left switches the clock to the warning colour, and that colour
+    pub warn: Option<(Duration, Color)>,
+    /// How far into the sound file the alarm starts
+    pub sound_offset: std::time::Duration,
+    /// Whether each repeat of the alarm reads the sound file again rather than reusing it
+    pub reload_sound: bool,
+    /// The sound files as they were at startup, so the alarm still plays if one is deleted or
+    /// moved while the timer runs
+    pub sound_cache: HashMap<String, Vec<u8>>,
+    pub human: bool,
+    pub freeze_on_trigger: bool,
+    /// Whether to draw the meter while the alarm plays, and how far along its animation is
+    pub visualizer: bool,
+    pub meter_ticks: usize,
+    pub bar_fill: bool,
+    pub duration_format: DurationFormat,
+    /// Whether to sleep out the last part of a tick so the alarm fires on time
+    pub precise: bool,
+    pub beep_every: Option<(Duration, BeepPattern)>,
+    /// How many `--beep-every` intervals were left on the last tick, and the beeps still to
+    /// ring, one per tick so they don't run together
+    pub intervals_left: Option<i64>,
+    pub pending_beeps: i64,
+    pub ticking: bool,
+    pub tick_sound: Option<String>,
+    /// The thread playing `--ticking`, sent one message per tick, and the second last ticked
+    pub ticker: Option<Sender<()>>,
+    pub last_tick_second: Option<i64>,
+    /// The sound to start the alarm with and how long before the usual one takes over
+    pub gentle: Option<(String, Duration)>,
+    pub gentle_stage: bool,
+    pub progress_direction: ProgressDirection,
+    /// Durations on offer in standby, and the one picked
+    pub presets: Vec<Duration>,
+    pub preset_index: usize,
+}
+
+fn random_color(rng: &mut impl Rng) -> Color {
+    let index: u8 = rng.gen_range(0..15);
+
+    match index {
+        1 => Color::Green,
+        2 => Color::Yellow,
+        3 => Color::Blue,
+        4 => Color::Magenta,
+        5 => Color::Cyan,
+        6 => Color::Gray,
+        7 => Color::DarkGray,
+        8 => Color::LightRed,
+        9 => Color::LightGreen,
+        10 => Color::LightYellow,
+        11 => Color::LightBlue,
+        12 => Color::LightMagenta,
+        13 => Color::LightCyan,
+        14 => Color::White,
+        _ => Color::Red, // 0 handled here
+    }
+}
+
+impl Default for App {
+    #[allow(clippy::arithmetic_side_effects)]
+    fn default() -> Self {
+        let duration = Duration::seconds(5);
+        let end_time = Local::now() + duration;
+        let seed = thread_rng().gen();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        Self {
+            running: true,
+            state: State::Running,
+            pre_pause_state: None,
+            duration,
+            time_left: duration,
+            end_time,
+            colour: random_color(&mut rng),
+            seed,
+            rng,
+            messages: Vec::new(),
+            stages: Vec::new(),
+            label_ticks: 0,
+            sound_file: String::from(""),
+            sender: None,
+            sound_thread: None,
+            audio_output: AudioOutput::Device,
+            keys: KeyMap::default(),
+            repeat: None,
+            repeat_interval: None,
+            queue: vec![Timer {
+                duration,
+                label: None,
+                sound: None,
+                is_break: false,
+            }],
+            queue_index: 0,
+            loop_queue: false,
+            round: 1,
+            sessions: None,
+            session: 1,
+            error: None,
+            start_time: None,
+            volume: 100,
+            muted: false,
+            flash: true,
+            flash_ticks: 0,
+            intro_ticks: 0,
+            fade: true,
+            last_adjustment: None,
+            font: None,
+            confirm_quit: false,
+            pre_quit_state: None,
+            finished: None,
+            completed: false,
+            plain: false,
+            wall_clock: false,
+            auto_exit: None,
+            alarm_duration: None,
+            triggered_at: None,
+            paused_total: Duration::zero(),
+            paused_at: None,
+            debug_keys: false,
+            quiet_hours: None,
+            marquee: false,
+            marquee_offset: 0,
+            count_up: false,
+            escalation: None,
+            escalation_level: None,
+            halfway_chime: false,
+            chimed: false,
+            start_chime: None,
+            extend_by: Duration::minutes(5),
+            footer: true,
+            bell_pattern: None,
+            bell_step: 0,
+            fixed_colour: false,
+            sound_every: 1,
+            alarm_silenced: false,
+            bells_rung: 0,
+            trigger_count: 0,
+            focus_sessions: 0,
+            focus_time: Duration::zero(),
+            break_time: Duration::zero(),
+            blocks: None,
+            hold_to_dismiss: false,
+            restart_sound_on_resume: false,
+            quiet_trigger: false,
+            speak: false,
+            show_info: false,
+            show_elapsed: false,
+            input: None,
+            input_error: None,
+            warn: None,
+            sound_offset: std::time::Duration::ZERO,
+            reload_sound: false,
+            sound_cache: HashMap::new(),
+            human: false,
+            freeze_on_trigger: false,
+            visualizer: false,
+            bar_fill: false,
+            meter_ticks: 0,
+            duration_format: DurationFormat::Hms,
+            precise: false,
+            beep_every: None,
+            intervals_left: None,
+            pending_beeps: 0,
+            ticking: false,
+            tick_sound: None,
+            ticker: None,
+            last_tick_second: None,
+            gentle: None,
+            gentle_stage: false,
+            progress_direction: ProgressDirection::Drain,
+            presets: Vec::new(),
+            preset_index: 0,
+            hold: None,
+        }
+    }
+}
+
+/// Sets up an `App` piece by piece, for constructing one without going through `Args`
+#[derive(Default)]
+pub struct AppBuilder {
+    queue: Vec<Timer>,
+    sound: Option<String>,
+    labels: Vec<String>,
+    stages: Vec<(Duration, String)>,
+    colour: Option<Color>,
+    volume: Option<u8>,
+    muted: bool,
+    keys: KeyMap,
+    max_duration: Option<Duration>,
+    start_time: Option<DateTime<Local>>,
+    repeat: Option<u32>,
+    repeat_interval: Option<std::time::Duration>,
+    loop_queue: bool,
+    no_flash: bool,
+    no_fade: bool,
+    font: Option<Font>,
+    confirm_quit: bool,
+    plain: bool,
+    wall_clock: bool,
+    auto_exit: Option<Duration>,
+    alarm_duration: Option<Duration>,
+    debug_keys: bool,
+    quiet_hours: Option<QuietHours>,
+    marquee: bool,
+    count_up: bool,
+    escalation: Option<Escalation>,
+    halfway_chime: bool,
+    start_chime: Option<String>,
+    extend_by: Option<Duration>,
+    no_footer: bool,
+    no_intro: bool,
+    bell_pattern: Option<BellPattern>,
+    sound_every: Option<u32>,
+    blocks: Option<u16>,
+    hold_to_dismiss: bool,
+    restart_sound_on_resume: bool,
+    quiet_trigger: bool,
+    speak: bool,
+    warn: Option<(Duration, Color)>,
+    sound_offset: Option<std::time::Duration>,
+    reload_sound: bool,
+    human: bool,
+    sessions: Option<u32>,
+    freeze_on_trigger: bool,
+    visualizer: bool,
+    bar_fill: bool,
+    seed: Option<u64>,
+    duration_format: DurationFormat,
+    precise: bool,
+    beep_every: Option<(Duration, BeepPattern)>,
+    ticking: bool,
+    tick_sound: Option<String>,
+    gentle: Option<(String, Duration)>,
+    progress_direction: Option<ProgressDirection>,
+    presets: Vec<Duration>,
+}
+
+impl AppBuilder {
+    /// Adds a timer of this duration to the queue
+    #[must_use]
+    pub fn duration(self, duration: Duration) -> Self {
+        self.timer(Timer {
+            duration,
+            label: None,
+            sound: None,
+            is_break: false,
+        })
+    }
+
+    /// Adds a timer with its own label and sound to the queue
+    #[must_use]
+    pub fn timer(mut self, timer: Timer) -> Self {
+        self.queue.push(timer);
+        self
+    }
+
+    /// The sound played by timers that don't have their own
+    #[must_use]
+    pub fn sound(mut self, path: impl Into<String>) -> Self {
+        self.sound = Some(path.into());
+        self
+    }
+
+    /// The label shown by timers that don't have their own. Given more than once, the labels
+    /// take turns on the triggered screen
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    /// A message shown from `at` into the countdown until the next stage starts
+    #[must_use]
+    pub fn stage(mut self, at: Duration, label: impl Into<String>) -> Self {
+        self.stages.push((at, label.into()));
+        self
+    }
+
+    /// The clock colour, picked at random if not set
+    #[must_use]
+    pub const fn colour(mut self, colour: Color) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    /// Alarm volume as a percentage
+    #[must_use]
+    pub const fn volume(mut self, volume: u8) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    #[must_use]
+    pub const fn muted(mut self, muted: bool) -> Self {
+        self.muted = muted;
+        self
+    }
+
+    #[must_use]
+    pub fn keys(mut self, keys: KeyMap) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Rejects any timer longer than this when building
+    #[must_use]
+    pub const fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Waits until this time before starting the countdown
+    #[must_use]
+    pub const fn start_time(mut self, start_time: DateTime<Local>) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Plays the alarm this many times instead of looping it until dismissed
+    #[must_use]
+    pub const fn repeat(mut self, repeat: u32) -> Self {
+        self.repeat = Some(repeat);
+        self
+    }
+
+    /// Pauses this long between plays of the alarm
+    #[must_use]
+    pub const fn repeat_interval(mut self, interval: std::time::Duration) -> Self {
+        self.repeat_interval = Some(interval);
+        self
+    }
+
+    /// Shows a rough description of the time left under the clock
+    #[must_use]
+    pub const fn human(mut self, human: bool) -> Self {
+        self.human = human;
+        self
+    }
+
+    /// Runs the timer `sessions` times, one after another as each alarm is dismissed
+    #[must_use]
+    pub const fn sessions(mut self, sessions: u32) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    /// Holds the clock at zero once the timer goes off rather than showing the overtime
+    #[must_use]
+    pub const fn freeze_on_trigger(mut self, freeze: bool) -> Self {
+        self.freeze_on_trigger = freeze;
+        self
+    }
+
+    /// Draws a pulsing meter while the alarm plays
+    #[must_use]
+    pub const fn visualizer(mut self, visualizer: bool) -> Self {
+        self.visualizer = visualizer;
+        self
+    }
+
+    /// Shades the screen behind the clock in proportion to the time left
+    #[must_use]
+    pub const fn bar_fill(mut self, bar_fill: bool) -> Self {
+        self.bar_fill = bar_fill;
+        self
+    }
+
+    /// Picks the random colours with this seed, to repeat the ones from another run
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Writes the time on the clock in this format
+    #[must_use]
+    pub fn duration_format(mut self, format: DurationFormat) -> Self {
+        self.duration_format = format;
+        self
+    }
+
+    /// Fires the alarm when the countdown actually ends rather than on the following tick
+    #[must_use]
+    pub const fn precise(mut self, precise: bool) -> Self {
+        self.precise = precise;
+        self
+    }
+
+    /// Beeps as each `interval` of the countdown passes
+    #[must_use]
+    pub const fn beep_every(mut self, interval: Duration, pattern: BeepPattern) -> Self {
+        self.beep_every = Some((interval, pattern));
+        self
+    }
+
+    /// Ticks every second while counting down
+    #[must_use]
+    pub const fn ticking(mut self, ticking: bool) -> Self {
+        self.ticking = ticking;
+        self
+    }
+
+    /// Ticks with this sound file rather than the built-in tick
+    #[must_use]
+    pub fn tick_sound(mut self, sound: impl Into<String>) -> Self {
+        self.tick_sound = Some(sound.into());
+        self
+    }
+
+    /// Starts the alarm with a softer sound, switching to the usual one after `after`
+    #[must_use]
+    pub fn gentle(mut self, sound: impl Into<String>, after: Duration) -> Self {
+        self.gentle = Some((sound.into(), after));
+        self
+    }
+
+    /// Starts the alarm this far into the sound file
+    #[must_use]
+    pub const fn sound_start_offset(mut self, offset: std::time::Duration) -> Self {
+        self.sound_offset = Some(offset);
+        self
+    }
+
+    /// Reads the sound file again for every repeat of the alarm instead of once per alarm
+    #[must_use]
+    pub const fn reload_sound(mut self, reload: bool) -> Self {
+        self.reload_sound = reload;
+        self
+    }
+
+    /// Starts the queue over from the first timer after the last one is dismissed
+    #[must_use]
+    pub const fn repeat_whole_session(mut self, loop_queue: bool) -> Self {
+        self.loop_queue = loop_queue;
+        self
+    }
+
+    #[must_use]
+    pub const fn flash(mut self, flash: bool) -> Self {
+        self.no_flash = !flash;
+        self
+    }
+
+    #[must_use]
+    pub const fn fade(mut self, fade: bool) -> Self {
+        self.no_fade = !fade;
+        self
+    }
+
+    #[must_use]
+    pub const fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    #[must_use]
+    pub const fn confirm_quit(mut self, confirm_quit: bool) -> Self {
+        self.confirm_quit = confirm_quit;
+        self
+    }
+
+    /// Draws everything in the terminal's default colours instead of the clock colour
+    #[must_use]
+    pub const fn plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        self
+    }
+
+    /// Shows the current time above the countdown
+    #[must_use]
+    pub const fn wall_clock(mut self, wall_clock: bool) -> Self {
+        self.wall_clock = wall_clock;
+        self
+    }
+
+    /// Silences the alarm once it has played this long, leaving it on screen until
+    /// `auto_exit` moves on
+    #[must_use]
+    pub const fn alarm_duration(mut self, duration: Duration) -> Self {
+        self.alarm_duration = Some(duration);
+        self
+    }
+
+    /// Dismisses the alarm on its own once it has played this long, exiting after the last timer
+    #[must_use]
+    pub const fn auto_exit(mut self, after: Duration) -> Self {
+        self.auto_exit = Some(after);
+        self
+    }
+
+    /// Enables the t key for skipping to the end of the countdown
+    #[must_use]
+    pub const fn debug_keys(mut self, debug_keys: bool) -> Self {
+        self.debug_keys = debug_keys;
+        self
+    }
+
+    /// Turns the alarm down during this window each day
+    #[must_use]
+    pub const fn quiet_hours(mut self, quiet_hours: QuietHours) -> Self {
+        self.quiet_hours = Some(quiet_hours);
+        self
+    }
+
+    /// Scrolls labels too long for the screen
+    #[must_use]
+    pub const fn marquee(mut self, marquee: bool) -> Self {
+        self.marquee = marquee;
+        self
+    }
+
+    /// Shows the time elapsed counting up towards each timer's duration, which carries on
+    /// counting after the alarm sounds
+    #[must_use]
+    pub const fn count_up(mut self, count_up: bool) -> Self {
+        self.count_up = count_up;
+        self
+    }
+
+    /// Starts the alarm quietly and turns it up the longer it is ignored
+    #[must_use]
+    pub fn escalate(mut self, escalation: Escalation) -> Self {
+        self.escalation = Some(escalation);
+        self
+    }
+
+    /// Plays a short snippet of the alarm once half the time has passed
+    #[must_use]
+    pub const fn halfway_chime(mut self, halfway_chime: bool) -> Self {
+        self.halfway_chime = halfway_chime;
+        self
+    }
+
+    /// Plays the start of this sound when the countdown begins
+    #[must_use]
+    pub fn start_chime(mut self, path: impl Into<String>) -> Self {
+        self.start_chime = Some(path.into());
+        self
+    }
+
+    /// How much time the extend key gives a timer that has gone off
+    #[must_use]
+    pub const fn extend_by(mut self, amount: Duration) -> Self {
+        self.extend_by = Some(amount);
+        self
+    }
+
+    /// Shows the keys that do something right now along the bottom
+    #[must_use]
+    pub const fn footer(mut self, footer: bool) -> Self {
+        self.no_footer = !footer;
+        self
+    }
+
+    /// Shows the duration that was set over the clock for a moment after launch, so a typo in
+    /// it is caught straight away
+    #[must_use]
+    pub const fn intro(mut self, intro: bool) -> Self {
+        self.no_intro = !intro;
+        self
+    }
+
+    /// Rings the terminal bell in this rhythm while the alarm goes off
+    #[must_use]
+    pub fn bell_pattern(mut self, pattern: BellPattern) -> Self {
+        self.bell_pattern = Some(pattern);
+        self
+    }
+
+    /// Plays the alarm only every nth time a timer goes off
+    #[must_use]
+    pub const fn sound_every(mut self, n: u32) -> Self {
+        self.sound_every = Some(n);
+        self
+    }
+
+    /// Shows the time left as a row of this many blocks below the clock
+    #[must_use]
+    pub const fn blocks(mut self, blocks: u16) -> Self {
+        self.blocks = Some(blocks);
+        self
+    }
+
+    /// Starts in standby, offering these durations to pick from
+    #[must_use]
+    pub fn presets(mut self, presets: Vec<Duration>) -> Self {
+        self.presets = presets;
+        self
+    }
+
+    /// Whether the row of blocks drains or fills as time passes
+    #[must_use]
+    pub const fn progress_direction(mut self, direction: ProgressDirection) -> Self {
+        self.progress_direction = Some(direction);
+        self
+    }
+
+    /// Requires holding the pause key down to dismiss the alarm
+    #[must_use]
+    pub const fn hold_to_dismiss(mut self, hold_to_dismiss: bool) -> Self {
+        self.hold_to_dismiss = hold_to_dismiss;
+        self
+    }
+
+    /// Plays the alarm from the start when it's unmuted
+    #[must_use]
+    pub const fn restart_sound_on_resume(mut self, restart_sound_on_resume: bool) -> Self {
+        self.restart_sound_on_resume = restart_sound_on_resume;
+        self
+    }
+
+    /// Keeps every sound off for this run, leaving the alarm to the screen
+    #[must_use]
+    pub const fn quiet_trigger(mut self, quiet_trigger: bool) -> Self {
+        self.quiet_trigger = quiet_trigger;
+        self
+    }
+
+    /// Reads the label aloud when the timer goes off
+    #[must_use]
+    pub const fn speak(mut self, speak: bool) -> Self {
+        self.speak = speak;
+        self
+    }
+
+    /// Draws the clock in `colour` once less than `threshold` is left
+    #[must_use]
+    pub const fn warn(mut self, threshold: Duration, colour: Color) -> Self {
+        self.warn = Some((threshold, colour));
+        self
+    }
+
+    /// Checks the settings fit together and creates the `App`
+    #[allow(clippy::indexing_slicing)]
+    pub fn build(mut self) -> Result<App> {
+        if let (true, Some(&first)) = (self.queue.is_empty(), self.presets.first()) {
+            self = self.duration(first);
+        }
+
+        if self.queue.is_empty() {
+            return Err("no timer duration given".into());
+        }
+
+        let durations = self.queue.iter().map(|timer| timer.duration);
+        if durations
+            .chain(self.presets.iter().copied())
+            .any(|duration| duration <= Duration::zero())
+        {
+            return Err(
+                "a timer has to be longer than zero, give -t a duration like 5m or 00:05:00".into(),
+            );
+        }
+
+        if let Some(max_duration) = self.max_duration {
+            let durations = self.queue.iter().map(|timer| timer.duration);
+            if let Some(duration) = durations
+                .chain(self.presets.iter().copied())
+                .find(|&duration| duration > max_duration)
+            {
+                return Err(format!(
+                    "timer duration {} is longer than the maximum of {}, raise --max-duration if this is intended",
+                    format_hms(duration),
+                    format_hms(max_duration)
+                )
+                .into());
+            }
+        }
+
+        let longest = self.queue.iter().map(|timer| timer.duration).max().unwrap_or_else(Duration::zero);
+        if let Some((at, label)) = self.stages.iter().find(|&&(at, _)| at >= longest) {
+            return Err(format!(
+                "stage {label:?} starts at {}, which is past the end of the {} timer",
+                format_hms(*at),
+                format_hms(longest)
+            )
+            .into());
+        }
+        self.stages.sort_by_key(|&(at, _)| at);
+
+        if self.sound_every == Some(0) {
+            return Err("the alarm can't play every 0th time".into());
+        }
+
+        if self.volume.is_some_and(|volume| volume > 100) {
+            return Err("volume must be between 0 and 100".into());
+        }
+
+        let duration = self.queue[0].duration;
+        let end_time = self
+            .start_time
+            .unwrap_or_else(Local::now)
+            .checked_add_signed(duration)
+            .ok_or("timer would end too far in the future")?;
+
+        let defaults = App::default();
+        let seed = self.seed.unwrap_or(defaults.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let random_colour = random_color(&mut rng);
+        log::info!("random seed {seed}, pass --seed {seed} for the same colours again");
+        let intro = !self.no_intro && !self.count_up && self.presets.is_empty();
+
+        let mut app = App {
+            state: if !self.presets.is_empty() {
+                State::Standby
+            } else if self.start_time.is_some() {
+                State::Waiting
+            } else {
+                State::Running
+            },
+            start_time: self.start_time,
+            colour: self.colour.unwrap_or(random_colour),
+            seed,
+            rng,
+            fixed_colour: self.colour.is_some(),
+            volume: self.volume.unwrap_or(100),
+            muted: self.muted,
+            flash: !self.no_flash,
+            fade: !self.no_fade,
+            font: self.font,
+            confirm_quit: self.confirm_quit,
+            duration,
+            time_left: duration,
+            end_time,
+            messages: self.labels,
+            stages: self.stages,
+            sound_file: self.sound.unwrap_or_default(),
+            keys: self.keys,
+            repeat: self.repeat,
+            repeat_interval: self.repeat_interval,
+            queue: self.queue,
+            loop_queue: self.loop_queue,
+            plain: self.plain,
+            wall_clock: self.wall_clock,
+            auto_exit: self.auto_exit,
+            alarm_duration: self.alarm_duration,
+            debug_keys: self.debug_keys,
+            quiet_hours: self.quiet_hours,
+            marquee: self.marquee,
+            count_up: self.count_up,
+            escalation: self.escalation,
+            halfway_chime: self.halfway_chime,
+            start_chime: self.start_chime,
+            extend_by: self.extend_by.unwrap_or(defaults.extend_by),
+            footer: !self.no_footer,
+            intro_ticks: if intro { INTRO_TICKS } else { 0 },
+            bell_pattern: self.bell_pattern,
+            sound_every: self.sound_every.unwrap_or(1),
+            blocks: self.blocks,
+            hold_to_dismiss: self.hold_to_dismiss,
+            restart_sound_on_resume: self.restart_sound_on_resume,
+            quiet_trigger: self.quiet_trigger,
+            speak: self.speak,
+            warn: self.warn,
+            sound_offset: self.sound_offset.unwrap_or_default(),
+            reload_sound: self.reload_sound,
+            human: self.human,
+            sessions: self.sessions,
+            freeze_on_trigger: self.freeze_on_trigger,
+            visualizer: self.visualizer,
+            bar_fill: self.bar_fill,
+            duration_format: self.duration_format,
+            precise: self.precise,
+            beep_every: self.beep_every,
+            ticking: self.ticking,
+            tick_sound: self.tick_sound,
+            gentle: self.gentle,
+            progress_direction: self.progress_direction.unwrap_or(defaults.progress_direction),
+            presets: self.presets,
+            ..defaults
+        };
+        app.cache_sounds();
+
+        Ok(app)
+    }
+}
+
+impl App {
+    #[must_use]
+    pub fn builder() -> AppBuilder {
+        AppBuilder::default()
+    }
+
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn new(mut args: Args, config: Config) -> Result<Self> {
+        if let (Some(_), Some(setup)) = (&args.common.save_config, args.unsavable()) {
+            return Err(format!("--save-config can only save a single timer, not {setup}").into());
+        }
+
+        if let Some(time) = args.countdown.until {
+            let now = Local::now();
+            let end = next_occurrence(time, &now)
+                .ok_or_else(|| format!("could not work out when it is next {time}"))?;
+            // Round up so the alarm doesn't go off before the clock reaches the time
+            let milliseconds = (end - now).num_milliseconds();
+            args.countdown.time.push(Duration::seconds((milliseconds + 999) / 1000));
+        }
+
+        // A timer saved with --save-config stands in when none is given on the command line
+        let saved = config.timer;
+        if args.countdown.time.is_empty()
+            && args.countdown.countup_target.is_none()
+            && args.queue.is_empty()
+            && args.countdown.schedule.is_none()
+            && args.countdown.presets.is_empty()
+        {
+            let duration = saved
+                .duration
+                .as_deref()
+                .map(parse_duration)
+                .transpose()
+                .map_err(|e| format!("invalid duration in config: {e}"))?;
+
+            match (saved.mode, duration) {
+                (Some(TimerMode::Stopwatch), duration) => {
+                    args.countdown.countup_target = Some(duration.unwrap_or(args.common.max_duration));
+                    args.stopwatch = true;
+                }
+                (_, Some(duration)) => args.countdown.time.push(duration),
+                (_, None) => {}
+            }
+        }
+        if args.common.sound.is_empty() {
+            args.common.sound.extend(saved.sound);
+        }
+        let other_sounds = [
+            args.common.gentle_sound.as_mut(),
+            args.common.escalate_sound.as_mut(),
+            args.common.tick_sound.as_mut(),
+            args.common.sound_start.as_mut().and_then(Option::as_mut),
+        ];
+        for sound in args.common.sound.iter_mut().chain(other_sounds.into_iter().flatten()) {
+            *sound = expand_sound(sound)?;
+        }
+        if args.common.label.is_empty() {
+            args.common.label.extend(saved.label);
+        }
+
+        let start_time = match (args.common.start_at, args.common.start_in) {
+            (Some(time), _) => Some(
+                next_occurrence(time, &Local::now())
+                    .ok_or_else(|| format!("could not work out when it is next {time}"))?,
+            ),
+            (None, Some(delay)) => Some(
+                Local::now()
+                    .checked_add_signed(delay)
+                    .ok_or("start time is too far in the future")?,
+            ),
+            (None, None) => None,
+        };
+
+        if args.common.require_audio {
+            check_audio()?;
+        }
+        if args.countdown.sessions.is_some() && (args.countdown.time.len() > 1 || !args.queue.is_empty()) {
+            return Err("--sessions repeats a single timer, so give only one -t".into());
+        }
+
+        let mut builder = Self::builder()
+            .max_duration(args.common.max_duration)
+            .keys(config.keys)
+            .muted(config.preferences.muted.unwrap_or(false))
+            .flash(!args.common.no_flash)
+            .fade(!args.common.no_fade)
+            .confirm_quit(args.common.confirm_quit)
+            .repeat_whole_session(args.common.repeat_whole_session)
+            .wall_clock(args.common.clock)
+            .debug_keys(args.common.debug_keys)
+            .marquee(args.common.marquee)
+            .count_up(args.countdown.countup_target.is_some())
+            .halfway_chime(args.common.halfway_chime)
+            .extend_by(args.common.extend)
+            .footer(!args.common.no_footer)
+            .intro(!args.common.no_intro)
+            .hold_to_dismiss(args.common.hold_to_dismiss)
+            .restart_sound_on_resume(args.common.restart_sound_on_resume)
+            .quiet_trigger(args.common.quiet_trigger)
+            .speak(args.common.speak)
+            .reload_sound(args.common.repeat_from_file)
+            .human(args.common.human)
+            .freeze_on_trigger(args.common.freeze_on_trigger)
+            .visualizer(args.common.visualizer)
+            .bar_fill(args.common.bar_fill)
+            .precise(args.common.precise)
+            .ticking(args.common.ticking)
+            .progress_direction(args.common.progress_direction)
+            .plain(
+                args.common.no_color
+                    || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+                    || !colour_supported(),
+            );
+
+        for (i, &duration) in args.countdown.time.iter().chain(&args.countdown.countup_target).enumerate() {
+            builder = builder.timer(Timer {
+                duration,
+                label: None,
+                sound: args.common.sound.get(i).cloned(),
+                is_break: false,
+            });
+        }
+
+        for timer in args.queue {
+            builder = builder.timer(timer);
+        }
+
+        if args.common.sound.is_empty() && !args.stopwatch && !args.common.speak && args.countdown.schedule.is_none() {
+            return Err("no alarm sound given, pass one with -s".into());
+        }
+
+        if let Some(path) = &args.countdown.schedule {
+            for (i, timer) in schedule::load(path)?.into_iter().enumerate() {
+                if timer.sound.is_none() && args.common.sound.is_empty() {
+                    return Err(format!(
+                        "timer {} in {} has no sound, give it one or pass -s",
+                        i.saturating_add(1),
+                        path.display()
+                    )
+                    .into());
+                }
+                builder = builder.timer(timer);
+            }
+        }
+
+        if let Some(path) = args.common.sound_start {
+            if let Some(path) = path.or_else(|| args.common.sound.first().cloned()) {
+                builder = builder.start_chime(path);
+            }
+        }
+        if let Some(sound) = args.common.sound.into_iter().next() {
+            builder = builder.sound(sound);
+        }
+        for label in args.common.label {
+            builder = builder.label(label);
+        }
+        for (at, label) in args.common.stage {
+            builder = builder.stage(at, label);
+        }
+        if let Some(volume) = args.common.volume.or(config.preferences.volume) {
+            builder = builder.volume(volume);
+        }
+        if let Some(start_time) = start_time {
+            builder = builder.start_time(start_time);
+        }
+        if let Some(repeat) = args.common.repeat.or(args.common.once.then_some(1)) {
+            builder = builder.repeat(repeat);
+        }
+        if let Some(interval) = args.common.repeat_interval {
+            builder = builder.repeat_interval(std::time::Duration::from_secs(interval));
+        }
+        if let Some(interval) = args.common.beep_every {
+            if interval <= Duration::zero() {
+                return Err("--beep-every must be longer than zero".into());
+            }
+            builder = builder.beep_every(interval, args.common.beep_pattern);
+        }
+        if let Some(sessions) = args.countdown.sessions {
+            builder = builder.sessions(sessions);
+        }
+        if let Some(seed) = args.common.seed {
+            builder = builder.seed(seed);
+        }
+        if let Some(sound) = args.common.tick_sound {
+            builder = builder.tick_sound(sound);
+        }
+        if !args.countdown.presets.is_empty() {
+            builder = builder.presets(args.countdown.presets);
+        }
+        if let Some(sound) = args.common.gentle_sound {
+            builder = builder.gentle(sound, args.common.gentle_for);
+        }
+        if let Some(format) = &args.common.duration_format {
+            let format = DurationFormat::parse(format).unwrap_or_else(|e| {
+                log::warn!("{e}, using hms");
+                DurationFormat::Hms
+            });
+            builder = builder.duration_format(format);
+        }
+        if let Some(offset) = args.common.sound_start_offset {
+            builder = builder.sound_start_offset(std::time::Duration::from_secs(offset));
+        }
+        if let Some(font) = args.common.font {
+            builder = builder.font(font);
+        }
+        if let Some(quiet_hours) = config.quiet_hours {
+            builder = builder.quiet_hours(quiet_hours);
+        }
+        if args.common.escalate {
+            builder = builder.escalate(Escalation {
+                step: args.common.escalate_step,
+                interval: std::time::Duration::from_secs(args.common.escalate_interval),
+                sound: args.common.escalate_sound,
+            });
+        }
+        if let Some(blocks) = args.common.blocks {
+            builder = builder.blocks(blocks);
+        }
+        if let Some(n) = args.common.sound_every {
+            builder = builder.sound_every(n);
+        }
+        if let Some(pattern) = args.common.bell_pattern {
+            builder = builder.bell_pattern(pattern);
+        }
+        if let Some(after) = args.common.auto_exit {
+            builder = builder.auto_exit(after);
+        }
+        if let Some(duration) = args.common.alarm_duration {
+            builder = builder.alarm_duration(duration);
+        }
+        if let (Some(threshold), Some(colour)) = (args.common.warn_threshold, args.common.warn_color) {
+            builder = builder.warn(threshold, colour);
+        }
+        if let Some(colour) = args.common.color {
+            builder = builder.colour(colour);
+        } else if let Some(colour) = &saved.colour {
+            let colour =
+                parse_colour(colour).map_err(|e| format!("invalid colour in config: {e}"))?;
+            builder = builder.colour(colour);
+        }
+
+        builder.build()
+    }
+
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn tick(&mut self) {
+        if self.marquee {
+            self.marquee_offset = self.marquee_offset.wrapping_add(1);
+        }
+        self.intro_ticks = self.intro_ticks.saturating_sub(1);
+
+        match self.state {
+            State::Standby => {}
+            State::Waiting => {
+                if self.start_time.is_none_or(|start_time| start_time <= Local::now()) {
+                    self.start();
+                }
+            }
+            // The time left was frozen on pausing, and the end time is only worked out again
+            // on resuming
+            State::Paused => {}
+            State::Quitting if self.pre_quit_state == Some(State::Paused) => {}
+            State::Running | State::Restart | State::Quitting => {
+                self.time_left = self.end_time.signed_duration_since(Local::now());
+
+                // The next tick would come too late, so wait out the rest here instead
+                let remaining = self.time_left.to_std().unwrap_or_default();
+                if self.precise
+                    && !remaining.is_zero()
+                    && remaining < std::time::Duration::from_millis(TICK_RATE)
+                {
+                    thread::sleep(remaining);
+                    self.time_left = self.end_time.signed_duration_since(Local::now());
+                }
+
+                self.countdown_beeps();
+                self.play_ticks();
+
+                if self.time_left <= Duration::zero() {
+                    self.trigger();
+                } else if self.halfway_chime && !self.chimed && self.time_left * 2 <= self.duration {
+                    self.chimed = true;
+                    let sound_file = self.sound_file().to_owned();
+                    self.error = self
+                        .chime(&sound_file)
+                        .err()
+                        .map(|e| format!("Error playing sound: {e}"));
+                }
+            }
+            State::Triggered => {
+                if !self.freeze_on_trigger {
+                    self.time_left = self.end_time.signed_duration_since(Local::now());
+                }
+                self.flash_ticks = self.flash_ticks.saturating_sub(1);
+                self.label_ticks = self.label_ticks.wrapping_add(1);
+                self.meter_ticks = self.meter_ticks.wrapping_add(1);
+                self.ring_bell_pattern();
+                let alarm_played = self.triggered_at.map(|at| Local::now() - at);
+                if let (Some(limit), Some(played)) = (self.alarm_duration, alarm_played) {
+                    if played >= limit && !self.alarm_silenced {
+                        self.alarm_silenced = true;
+                        self.stop_sound();
+                    }
+                }
+
+                self.escalate();
+                self.end_gentle_stage();
+
+                if self.hold.is_some_and(|(_, last)| last.elapsed() > HOLD_GAP) {
+                    self.hold = None;
+                }
+
+                if let (Some(limit), Some(played)) = (self.auto_exit, alarm_played) {
+                    if played >= limit {
+                        self.auto_dismiss();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stops a running countdown, freezing the time left as it is right now
+    pub fn pause(&mut self) {
+        if self.state == State::Running {
+            let now = Local::now();
+
+            self.time_left = self.end_time.signed_duration_since(now);
+            self.paused_at = Some(now);
+            self.pre_pause_state = Some(self.state);
+            self.state = State::Paused;
+        }
+    }
+
+    /// Carries on a paused countdown from the time left, counting the pause towards the total
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn resume(&mut self) {
+        if self.state == State::Paused {
+            let now = Local::now();
+
+            if let Some(paused_at) = self.paused_at.take() {
+                self.paused_total = self.paused_total + (now - paused_at);
+            }
+            self.end_time = now + self.time_left;
+            self.state = self.pre_pause_state.take().unwrap_or(State::Running);
+        }
+    }
+
+    /// When the countdown ends, or would if a paused one were resumed now
+    #[must_use]
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn projected_end_time(&self) -> DateTime<Local> {
+        if self.state == State::Paused {
+            Local::now() + self.time_left
+        } else {
+            self.end_time
+        }
+    }
+
+    fn trigger(&mut self) {
+        self.trigger_count = self.trigger_count.saturating_add(1);
+        let silent_cycle = !self.trigger_count.is_multiple_of(self.sound_every.max(1));
+        self.alarm_silenced = silent_cycle || self.quiet_volume() == Some(0);
+
+        self.gentle_stage = self.gentle.is_some();
+
+        self.escalation_level = self.escalation.as_ref().map(|escalation| escalation.step);
+        let speech_only = self.speak && self.sound_file().is_empty();
+        self.error = if self.alarm_silenced || speech_only {
+            None
+        } else {
+            self.start_sound()
+                .err()
+                .map(|e| format!("Error playing sound: {e}"))
+        };
+
+        if self.speak && !self.quiet_trigger && !self.muted && !self.alarm_silenced {
+            let text = self.label().unwrap_or("Timer finished");
+            if let Err(e) = speech::speak(text) {
+                self.error.get_or_insert_with(|| format!("Error speaking the label: {e}"));
+            }
+        }
+
+        if self.flash {
+            self.flash_ticks = FLASH_TICKS;
+            if !self.alarm_silenced {
+                self.bell();
+            }
+        }
+
+        if let Some(error) = &self.error {
+            log::warn!("{error}");
+        }
+
+        self.state = State::Triggered;
+        self.pre_quit_state = None;
+        self.completed = !self.loop_queue
+            && self.queue_index.saturating_add(1) >= self.queue.len()
+            && self.sessions.is_none_or(|sessions| self.session >= sessions);
+        if self.freeze_on_trigger {
+            self.time_left = Duration::zero();
+        }
+        self.triggered_at = Some(Local::now());
+        self.bell_step = 0;
+        let is_break = self.queue.get(self.queue_index).is_some_and(|timer| timer.is_break);
+        if is_break {
+            self.break_time = self.break_time + self.duration;
+        } else {
+            self.focus_sessions = self.focus_sessions.saturating_add(1);
+            self.focus_time = self.focus_time + self.duration;
+        }
+
+        self.finished = Some(Timer {
+            duration: self.duration,
+            label: self.label().map(String::from),
+            sound: None,
+            is_break,
+        });
+    }
+
+    /// Queues beeps for `--beep-every` when another interval has passed, and rings the next
+    /// queued one
+    #[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+    fn countdown_beeps(&mut self) {
+        let Some((interval, pattern)) = self.beep_every else {
+            return;
+        };
+
+        let interval = interval.num_milliseconds().max(1);
+        let intervals_left = (self.time_left.num_milliseconds() + interval - 1) / interval;
+
+        let passed_one = self.intervals_left.is_some_and(|previous| intervals_left < previous);
+        if passed_one && intervals_left > 0 {
+            self.pending_beeps += match pattern {
+                BeepPattern::Count => intervals_left,
+                BeepPattern::Once => 1,
+            };
+        }
+        self.intervals_left = Some(intervals_left);
+
+        if self.pending_beeps > 0 {
+            self.pending_beeps -= 1;
+            self.bell();
+        }
+    }
+
+    /// Ticks each time the clock passes a whole second, for `--ticking`
+    fn play_ticks(&mut self) {
+        if !self.ticking || self.quiet_trigger || self.state != State::Running {
+            return;
+        }
+
+        let second = self.time_left.num_seconds();
+        let passed_one = self.last_tick_second.is_some_and(|last| second < last);
+        self.last_tick_second = Some(second);
+        if !passed_one || self.muted {
+            return;
+        }
+
+        if self.ticker.is_none() {
+            match self.start_ticker() {
+                Ok(ticker) => self.ticker = Some(ticker),
+                Err(e) => {
+                    log::warn!("could not play ticks: {e}");
+                    self.ticking = false;
+                    return;
+                }
+            }
+        }
+
+        if let Some(ticker) = &self.ticker {
+            let _result = ticker.send(());
+        }
+    }
+
+    /// Starts a thread that plays a tick each time it is sent a message, until the sender is
+    /// dropped
+    fn start_ticker(&self) -> Result<Sender<()>> {
+        let bytes = match &self.tick_sound {
+            Some(sound_file) => {
+                let bytes = self.sound_bytes(sound_file)?;
+                rodio::Decoder::new(Cursor::new(bytes.clone()))
+                    .map_err(|e| describe_decoder_error(sound_file, e))?;
+                Some(bytes)
+            }
+            None => None,
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let output = self.audio_output;
+        let volume = self.sink_volume();
+
+        thread::spawn(move || {
+            let Some((_output, sink)) = open_sink(output) else { return };
+            sink.set_volume(volume);
+
+            for () in rx {
+                let tick: Box<dyn Source<Item = f32> + Send> = match &bytes {
+                    Some(bytes) => match rodio::Decoder::new(Cursor::new(bytes.clone())) {
+                        Ok(decoder) => {
+                            Box::new(decoder.convert_samples().take_duration(TICK_SOUND_LENGTH))
+                        }
+                        Err(_) => continue,
+                    },
+                    None => Box::new(
+                        SineWave::new(1500.0)
+                            .take_duration(BUILT_IN_TICK_LENGTH)
+                            .amplify(0.2),
+                    ),
+                };
+                sink.append(tick);
+            }
+        });
+
+        Ok(tx)
+    }
+
+    /// Swaps the gentle sound for the usual alarm once it has played for long enough
+    #[allow(clippy::arithmetic_side_effects)]
+    fn end_gentle_stage(&mut self) {
+        let (Some((_, after)), Some(triggered_at)) = (&self.gentle, self.triggered_at) else {
+            return;
+        };
+
+        if !self.gentle_stage || Local::now() - triggered_at < *after {
+            return;
+        }
+
+        self.gentle_stage = false;
+        if self.sender.is_some() && !self.alarm_silenced {
+            self.stop_sound();
+            self.error = self
+                .start_sound()
+                .err()
+                .map(|e| format!("Error playing sound: {e}"));
+        }
+    }
+
+    /// Rings the terminal bell, unless `--quiet-trigger` keeps everything silent
+    fn bell(&mut self) {
+        if !self.quiet_trigger {
+            eprint!("\x07");
+            self.bells_rung = self.bells_rung.saturating_add(1);
+        }
+    }
+
+    /// Rings the bell if the `--bell-pattern` calls for it on this tick, unless the alarm is
+    /// being kept silent
+    fn ring_bell_pattern(&mut self) {
+        if let Some(pattern) = &self.bell_pattern {
+            if pattern.rings_on(self.bell_step) && !self.alarm_silenced {
+                self.bell();
+            }
+            self.bell_step = self.bell_step.wrapping_add(1);
+        }
+    }
+
+    /// Turns an ignored alarm up a step each escalation interval, switching to the louder sound
+    /// once it reaches full volume
+    #[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+    fn escalate(&mut self) {
+        let (Some(escalation), Some(triggered_at), Some(previous)) =
+            (&self.escalation, self.triggered_at, self.escalation_level)
+        else {
+            return;
+        };
+
+        let ignored_for = (Local::now() - triggered_at).to_std().unwrap_or_default();
+        let steps = ignored_for.as_millis() / escalation.interval.as_millis().max(1);
+        let level = u8::try_from((steps + 1) * u128::from(escalation.step))
+            .unwrap_or(100)
+            .min(100);
+
+        if level == previous {
+            return;
+        }
+
+        let louder_sound = escalation.sound.clone().filter(|_| !self.alarm_silenced);
+        self.escalation_level = Some(level);
+        self.send_volume();
+
+        if let Some(sound) = louder_sound.filter(|_| previous < self.volume && level >= self.volume) {
+            self.stop_sound();
+            self.error = self
+                .play_sound(&sound)
+                .err()
+                .map(|e| format!("Error playing sound: {e}"));
+        }
+    }
+
+    /// A line recording the last timer that went off, or what a looping session got done, for
+    /// printing once the TUI has closed
+    #[must_use]
+    pub fn summary(&self) -> Option<String> {
+        if self.loop_queue && self.focus_sessions > 0 {
+            return Some(self.focus_summary());
+        }
+
+        self.finished.as_ref().map(|timer| match &timer.label {
+            Some(label) => format!("cli-timer: \"{label}\" finished after {}", format_hms(timer.duration)),
+            None => format!("cli-timer: timer finished after {}", format_hms(timer.duration)),
+        })
+    }
+
+    /// Whether something on screen moves from tick to tick while the clock stands still, like
+    /// the marquee, the intro, the flash and everything drawn for a ringing alarm
+    #[must_use]
+    pub fn animating(&self) -> bool {
+        self.marquee || self.intro_ticks > 0 || self.flash_ticks > 0 || self.state == State::Triggered
+    }
+
+    /// How to get the same random colours again, printed on exit since the info screen that
+    /// also shows the seed is gone by then. Nothing when the colours weren't random
+    #[must_use]
+    pub fn seed_line(&self) -> Option<String> {
+        (!self.fixed_colour && !self.plain)
+            .then(|| format!("cli-timer: pass --seed {} for the same colours again", self.seed))
+    }
+
+    /// The clock colour, or the warning colour once the time left drops below the threshold
+    fn clock_colour(&self) -> Color {
+        match self.warn {
+            Some((threshold, colour))
+                if !matches!(self.state, State::Waiting | State::Standby | State::Triggered)
+                    && self.time_left < threshold =>
+            {
+                colour
+            }
+            _ => self.colour,
+        }
+    }
+
+    /// How much got done over a looping session, e.g. "completed 4 focus sessions, 100 min
+    /// focus and 20 min break in total"
+    fn focus_summary(&self) -> String {
+        let plural = if self.focus_sessions == 1 { "" } else { "s" };
+
+        format!(
+            "cli-timer: completed {} focus session{plural}, {} min focus and {} min break in total",
+            self.focus_sessions,
+            self.focus_time.num_minutes(),
+            self.break_time.num_minutes()
+        )
+    }
+
+    /// The base style for the whole screen, inverted on alternate ticks while flashing
+    #[allow(clippy::modulo_arithmetic)]
+    fn style(&self) -> Style {
+        let style = if self.plain {
+            Style::default()
+        } else {
+            Style::default().fg(self.clock_colour()).bg(Color::Black)
+        };
+
+        if self.flash_ticks % 2 == 1 {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    #[allow(clippy::indexing_slicing)]
+    pub fn render<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let mut area = frame.size();
+
+        if area.height < COMPACT_HEIGHT || area.width < COMPACT_WIDTH {
+            self.render_compact(frame, area);
+            return;
+        }
+
+        if self.footer && area.height >= FOOTER_MIN_HEIGHT {
+            area.height -= 1;
+            frame.render_widget(
+                self.footer_widget(area.width),
+                Rect {
+                    y: area.bottom(),
+                    height: 1,
+                    ..area
+                },
+            );
+        }
+
+        let clock = self.clock_lines(area);
+        let clock_height = u16::try_from(clock.len()).unwrap_or(u16::MAX);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    if clock_height == 1 {
+                        Constraint::Percentage(49)
+                    } else {
+                        Constraint::Length(area.height.saturating_sub(clock_height) / 2)
+                    },
+                    Constraint::Length(clock_height),
+                    Constraint::Min(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        let mut message_area = layout[2];
+
+        frame.render_widget(
+            Block::default().style(self.style()),
+            layout[0],
+        );
+
+        if let Some(blocks) = self.blocks.filter(|_| message_area.height > 1) {
+            frame.render_widget(
+                Paragraph::new(self.blocks_bar(blocks.min(message_area.width)))
+                    .style(self.style())
+                    .alignment(Alignment::Center),
+                Rect {
+                    height: 1,
+                    ..message_area
+                },
+            );
+            message_area.y += 1;
+            message_area.height -= 1;
+        }
+
+        if self.human && !self.count_up && message_area.height > 1 {
+            frame.render_widget(
+                Paragraph::new(human_time_left(self.time_left, self.overtime()))
+                    .style(self.style())
+                    .alignment(Alignment::Center),
+                Rect {
+                    height: 1,
+                    ..message_area
+                },
+            );
+            message_area.y += 1;
+            message_area.height -= 1;
+        }
+
+        if self.wall_clock && layout[0].height > 0 {
+            frame.render_widget(
+                Paragraph::new(Local::now().format("%H:%M:%S").to_string())
+                    .style(self.style())
+                    .alignment(Alignment::Center),
+                Rect {
+                    height: 1,
+                    ..layout[0]
+                },
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(clock.into_iter().map(Spans::from).collect::<Vec<_>>())
+                .block(Block::default().borders(Borders::NONE))
+                .style(self.style())
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let widget = match self.state {
+            State::Waiting
+            | State::Standby
+            | State::Paused
+            | State::Restart
+            | State::Quitting
+            | State::Triggered => {
+                let paragraph_string: Cow<'_, str> = match self.state {
+                    State::Standby => {
+                        " Pick a timer with the arrow keys and press Enter to start".into()
+                    },
+                    State::Waiting => {
+                        let start_time = self.start_time.unwrap_or(self.end_time);
+                        format!(" Starting at {}", start_time.format("%H:%M:%S")).into()
+                    },
+                    State::Paused => {
+                        " Paused".into()
+                    },
+                    State::Restart => {
+                        " Are you sure you want to restart the timer? (Press again to confirm, Esc/q to cancel)".into()
+                    },
+                    State::Quitting => {
+                        " Quit? (y/n)".into()
+                    },
+                    State::Triggered => match self.label() {
+                        Some(label) if self.marquee => {
+                            scroll(label, usize::from(message_area.width), self.marquee_offset)
+                        }
+                        Some(label) => label.into(),
+                        None if self.freeze_on_trigger => "Done".into(),
+                        None => "".into(),
+                    },
+                    State::Running => "".into(),
+                };
+
+                let mut lines = vec![Spans::from(Span::raw(paragraph_string))];
+                if self.state == State::Standby {
+                    lines.extend(self.preset_lines());
+                }
+                if let (State::Paused, Some(stage)) = (self.state, self.current_stage()) {
+                    lines.push(Spans::from(stage));
+                }
+                if let (State::Triggered, Some(meter)) = (self.state, self.meter()) {
+                    lines.push(Spans::from(meter));
+                }
+                if self.state == State::Triggered && self.hold_to_dismiss {
+                    lines.push(Spans::from(self.hold_text()));
+                }
+                if self.state == State::Triggered && self.paused_total >= Duration::seconds(1) {
+                    lines.push(Spans::from(format!(
+                        "Paused for {} total",
+                        format_hms(self.paused_total)
+                    )));
+                }
+                if let (State::Triggered, Some(error)) = (self.state, &self.error) {
+                    let style = if self.plain {
+                        Style::default().add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Red)
+                    };
+                    lines.push(Spans::from(Span::styled(error.as_str(), style)));
+                }
+                if let Some(adjustment) = self.adjustment_text() {
+                    lines.push(Spans::from(adjustment));
+                }
+
+                Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::NONE))
+                    .style(self.style())
+                    .alignment(Alignment::Center)
+            }
+            State::Running => {
+                let mut lines = Vec::new();
+                if let Some(stage) = self.current_stage() {
+                    lines.push(Spans::from(stage));
+                }
+                lines.push(Spans::from(self.adjustment_text().unwrap_or_default()));
+
+                Paragraph::new(lines)
+                    .block(Block::default().style(self.style()))
+                    .alignment(Alignment::Center)
+            }
+        };
+
+        if self.queue.len() > 1 {
+            let regions = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)].as_ref())
+                .split(message_area);
+
+            frame.render_widget(widget, regions[0]);
+            frame.render_widget(self.queue_widget(), regions[1]);
+        } else if let Some(sessions) = self.sessions {
+            let regions = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)].as_ref())
+                .split(message_area);
+
+            frame.render_widget(widget, regions[0]);
+            frame.render_widget(
+                Paragraph::new(format!("Session {}/{sessions}", self.session))
+                    .style(self.style())
+                    .alignment(Alignment::Center),
+                regions[1],
+            );
+        } else {
+            frame.render_widget(widget, message_area);
+        }
+
+        if self.bar_fill {
+            let style = if self.plain {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::Black).bg(self.clock_colour())
+            };
+            frame.render_widget(
+                Shade { style },
+                Rect {
+                    width: self.bar_fill_width(area.width),
+                    ..area
+                },
+            );
+        }
+
+        if self.show_info {
+            self.render_info(frame, area);
+        } else if let Some(input) = &self.input {
+            self.render_input(frame, area, input);
+        } else if self.intro_ticks > 0 {
+            let line = format!("{} timer started", format_hms(self.duration));
+            render_popup(frame, area, vec![Spans::from(line)], "", self.style());
+        }
+    }
+
+    /// A box for typing in a new duration, with the reason the last one was turned down
+    fn render_input<B: Backend>(&self, frame: &mut Frame<'_, B>, area: Rect, input: &str) {
+        let mut lines = vec![Spans::from(format!("{input:<24}"))];
+        if let Some(error) = &self.input_error {
+            let style = if self.plain {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            lines.push(Spans::from(Span::styled(error.as_str(), style)));
+        }
+
+        render_popup(frame, area, lines, " New duration (Enter to set) ", self.style());
+    }
+
+    /// A box in the middle of the screen with the version and the settings in use, for bug
+    /// reports
+    fn render_info<B: Backend>(&self, frame: &mut Frame<'_, B>, area: Rect) {
+        let volume = if self.muted {
+            format!("{}% (muted)", self.volume)
+        } else {
+            format!("{}%", self.volume)
+        };
+
+        let lines = vec![
+            Spans::from(format!(
+                "{} {}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )),
+            Spans::from(format!("Built {}", env!("BUILD_DATE"))),
+            Spans::from(""),
+            Spans::from(format!("Duration: {}", format_hms(self.duration))),
+            Spans::from(format!("Sound: {}", self.sound_file())),
+            Spans::from(format!("Volume: {volume}")),
+            Spans::from(format!("Seed: {}", self.seed)),
+        ];
+
+        render_popup(frame, area, lines, " Info (Esc to close) ", self.style());
+    }
+
+    /// A row of bars bouncing in time with the ticks, while the alarm is audible and
+    /// `--visualizer` is on. It follows the ticks rather than the sound, to stay cheap
+    #[allow(clippy::arithmetic_side_effects, clippy::indexing_slicing)]
+    fn meter(&self) -> Option<String> {
+        if !self.visualizer || self.sender.is_none() || self.muted {
+            return None;
+        }
+
+        let top = METER_LEVELS.len() - 1;
+        let bars = (0..METER_BARS)
+            .map(|bar| {
+                // A triangle wave per bar, each a little out of step with its neighbours
+                let phase = (bar * 3 + self.meter_ticks * 2) % (top * 2);
+                METER_LEVELS[if phase > top { top * 2 - phase } else { phase }]
+            })
+            .collect();
+
+        Some(bars)
+    }
+
+    /// The keys that do something in the current state, e.g. "[space] pause  [q] quit", as many
+    /// as fit in `width`
+    #[allow(clippy::arithmetic_side_effects)]
+    fn footer_widget(&self, width: u16) -> Paragraph<'_> {
+        let hints = key_hints(self.state)
+            .iter()
+            .map(|&(key, description)| match key {
+                HintKey::Fixed(key) => format!("[{key}] {description}"),
+                HintKey::Bound(action) => match self.keys.key(action) {
+                    ' ' => format!("[space] {description}"),
+                    key => format!("[{key}] {description}"),
+                },
+            });
+
+        // The hints run from most to least useful, so whatever doesn't fit is dropped off the end
+        let mut shown: Vec<String> = Vec::new();
+        let mut used = 0;
+        for hint in hints {
+            let needed = hint.chars().count() + if shown.is_empty() { 0 } else { 2 };
+            if used + needed > usize::from(width) {
+                break;
+            }
+            used += needed;
+            shown.push(hint);
+        }
+
+        let style = if self.plain {
+            self.style()
+        } else {
+            self.style().fg(Color::DarkGray)
+        };
+
+        Paragraph::new(shown.join("  "))
+            .style(style)
+            .alignment(Alignment::Center)
+    }
+
+    /// Just the clock on a single line, for terminals too small for the full layout
+    fn render_compact<B: Backend>(&self, frame: &mut Frame<'_, B>, area: Rect) {
+        frame.render_widget(Block::default().style(self.style()), area);
+        frame.render_widget(
+            Paragraph::new(self.time_left_string().trim_start().to_owned())
+                .style(self.style())
+                .alignment(Alignment::Center),
+            Rect {
+                y: area.y.saturating_add(area.height / 2),
+                height: area.height.min(1),
+                ..area
+            },
+        );
+    }
+
+    /// How much of the current timer has elapsed, from 0 to 100
+    #[must_use]
+    #[allow(clippy::arithmetic_side_effects, clippy::float_arithmetic, clippy::cast_precision_loss)]
+    pub fn percent_complete(&self) -> f64 {
+        let total = self.duration.num_milliseconds();
+        if total <= 0 {
+            return 100.0;
+        }
+
+        let elapsed = (self.duration - self.time_left).num_milliseconds();
+        (elapsed as f64 / total as f64 * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// The `--stage` message for how far into the countdown it is, if one has started
+    #[must_use]
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn current_stage(&self) -> Option<&str> {
+        let elapsed = self.duration - self.time_left;
+        self.stages
+            .iter()
+            .rev()
+            .find(|&&(at, _)| at <= elapsed)
+            .map(|(_, label)| label.as_str())
+    }
+
+    /// A prompt to hold the dismiss key, with a bar filling up while it is held
+    #[allow(
+        clippy::float_arithmetic,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::arithmetic_side_effects
+    )]
+    fn hold_text(&self) -> String {
+        const WIDTH: usize = 10;
+
+        match self.hold_progress() {
+            Some(progress) => {
+                let filled = ((WIDTH as f64) * progress).round() as usize;
+                format!("Keep holding {}", "▓".repeat(filled) + &"░".repeat(WIDTH - filled.min(WIDTH)))
+            }
+            None => String::from("Hold the pause key to dismiss"),
+        }
+    }
+
+    /// A row of blocks for the time left, emptied from the left as time passes, or for the time
+    /// elapsed, filled from the left, with `--progress-direction fill`
+    #[allow(
+        clippy::float_arithmetic,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::arithmetic_side_effects
+    )]
+    fn blocks_bar(&self, blocks: u16) -> String {
+        let complete = self.percent_complete() / 100.0;
+
+        match self.progress_direction {
+            ProgressDirection::Drain => {
+                let full = ((f64::from(blocks) * (1.0 - complete)).ceil() as u16).min(blocks);
+                "░".repeat(usize::from(blocks - full)) + &"▓".repeat(usize::from(full))
+            }
+            ProgressDirection::Fill => {
+                let full = ((f64::from(blocks) * complete).floor() as u16).min(blocks);
+                "▓".repeat(usize::from(full)) + &"░".repeat(usize::from(blocks - full))
+            }
+        }
+    }
+
+    /// How many columns of the screen `--bar-fill` shades, in proportion to the time left. Once
+    /// the timer goes off the empty bar flashes across the whole width
+    #[allow(
+        clippy::float_arithmetic,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    #[must_use]
+    pub fn bar_fill_width(&self, width: u16) -> u16 {
+        if self.state == State::Triggered {
+            return if self.label_ticks % 2 == 1 { width } else { 0 };
+        }
+
+        let left = 1.0 - self.percent_complete() / 100.0;
+        ((f64::from(width) * left).ceil() as u16).min(width)
+    }
+
+    /// The clock drawn in the chosen font, or as plain text without one or when it doesn't fit
+    fn clock_lines(&self, area: Rect) -> Vec<String> {
+        if let Some(font) = self.font {
+            let mut lines = font.render(&self.time_left_string());
+
+            if self.clock_overtime() {
+                lines.push(String::from("overtime"));
+            }
+
+            let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+            if width <= usize::from(area.width) && lines.len() + 2 <= usize::from(area.height) {
+                return lines;
+            }
+        }
+
+        vec![self.clock_string()]
+    }
+
+    #[must_use]
+    pub fn clock_string(&self) -> String {
+        if self.clock_overtime() {
+            format!("{} overtime", self.time_left_string())
+        } else {
+            self.time_left_string()
+        }
+    }
+
+    /// The sign and time shown on the clock, e.g. " 01:30:00" or "+00:00:05"
+    fn time_left_string(&self) -> String {
+        let shown = self.clock_duration();
+        let hours = self.duration.num_hours().max(shown.num_hours().abs());
+
+        format_time_left(
+            shown,
+            self.clock_overtime(),
+            hours.to_string().len(),
+            &self.duration_format,
+        )
+    }
+
+    /// Whether the countdown has run past its end. Counting up just carries on, and
+    /// `--freeze-on-trigger` stays at zero
+    fn overtime(&self) -> bool {
+        self.state == State::Triggered && !self.count_up && !self.freeze_on_trigger
+    }
+
+    /// Whether the clock shows time past the end of the countdown, which it doesn't while
+    /// showing the time elapsed as that just keeps going
+    fn clock_overtime(&self) -> bool {
+        self.state == State::Triggered && !self.freeze_on_trigger && !self.shows_elapsed()
+    }
+
+    /// Whether the clock shows the time elapsed, as it does when counting up unless the d key
+    /// has flipped it
+    fn shows_elapsed(&self) -> bool {
+        self.count_up != self.show_elapsed
+    }
+
+    /// The time shown on the clock, either the time left or the time elapsed
+    #[allow(clippy::arithmetic_side_effects)]
+    fn clock_duration(&self) -> Duration {
+        if self.shows_elapsed() {
+            self.duration - self.time_left
+        } else {
+            self.time_left
+        }
+    }
+
+    fn queue_widget(&self) -> Paragraph<'_> {
+        let mut lines: Vec<Spans<'_>> = Vec::new();
+
+        if self.loop_queue {
+            lines.push(Spans::from(format!(" Round {}", self.round)));
+        }
+
+        lines.extend(self.queue.iter().enumerate().map(|(i, timer)| {
+            let text = match &timer.label {
+                Some(label) => format!(" {}  {label}", format_hms(timer.duration)),
+                None => format!(" {}", format_hms(timer.duration)),
+            };
+
+            let style = match (i.cmp(&self.queue_index), self.plain) {
+                (Ordering::Less, true) => Style::default().add_modifier(Modifier::DIM),
+                (Ordering::Equal, true) => Style::default().add_modifier(Modifier::REVERSED),
+                (Ordering::Greater, true) => Style::default(),
+                (Ordering::Less, false) => Style::default().fg(Color::DarkGray),
+                (Ordering::Equal, false) => Style::default().fg(Color::Black).bg(self.colour),
+                (Ordering::Greater, false) => Style::default().fg(self.colour),
+            };
+
+            Spans::from(Span::styled(text, style))
+        }));
+
+        Paragraph::new(lines)
+            .style(self.style())
+            .alignment(Alignment::Center)
+    }
+
+    /// The label of the current timer, falling back to the ones given with `-l` in turn
+    #[must_use]
+    #[allow(clippy::arithmetic_side_effects, clippy::integer_division, clippy::modulo_arithmetic)]
+    pub fn label(&self) -> Option<&str> {
+        self.queue
+            .get(self.queue_index)
+            .and_then(|timer| timer.label.as_deref())
+            .or_else(|| {
+                let turn = self.label_ticks / LABEL_TICKS;
+                self.messages
+                    .get(turn % self.messages.len().max(1))
+                    .map(String::as_str)
+            })
+    }
+
+    /// The sound for the current timer, falling back to the first one given with `-s`
+    #[must_use]
+    pub fn sound_file(&self) -> &str {
+        self.queue
+            .get(self.queue_index)
+            .and_then(|timer| timer.sound.as_deref())
+            .unwrap_or(&self.sound_file)
+    }
+
+    /// Moves on to the next queued timer, returning false if this was the last one
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn next_timer(&mut self) -> bool {
+        let Some(timer) = self.queue.get(self.queue_index + 1) else {
+            return false;
+        };
+
+        self.duration = timer.duration;
+        self.queue_index += 1;
+        self.restart();
+        true
+    }
+
+    /// Goes back to the first queued timer and counts another round
+    #[allow(clippy::indexing_slicing)]
+    pub fn first_timer(&mut self) {
+        self.duration = self.queue[0].duration;
+        self.queue_index = 0;
+        self.round = self.round.saturating_add(1);
+        self.restart();
+    }
+
+    /// Dismisses the alarm for `--auto-exit`, moving on while sessions remain or the queue loops
+    /// and exiting once the run is complete, rather than restarting a lone timer
+    fn auto_dismiss(&mut self) {
+        if self.completed {
+            self.running = false;
+        } else {
+            self.dismiss();
+        }
+
+        if !self.running {
+            self.stop_sound();
+        }
+    }
+
+    /// Counts a press of the dismiss key towards holding it down, dismissing once it has been
+    /// held long enough
+    pub fn hold_dismiss(&mut self) {
+        if self.held() {
+            self.dismiss();
+        }
+    }
+
+    /// Counts a key press towards holding a key down on the alarm under `--hold-to-dismiss`,
+    /// giving true once it has been held long enough. Key repeats arriving within `HOLD_GAP` of
+    /// each other keep the hold going
+    pub fn held(&mut self) -> bool {
+        let now = Instant::now();
+        let started = match self.hold {
+            Some((started, last)) if now.duration_since(last) <= HOLD_GAP => started,
+            _ => now,
+        };
+
+        if now.duration_since(started) >= HOLD_DURATION {
+            self.hold = None;
+            true
+        } else {
+            self.hold = Some((started, now));
+            false
+        }
+    }
 
-    /// Path to the sound file to use
-    #[arg(short)]
-    sound: String,
+    /// How far through holding down the dismiss key the user is, from 0 to 1
+    #[must_use]
+    #[allow(clippy::float_arithmetic)]
+    pub fn hold_progress(&self) -> Option<f64> {
+        self.hold.map(|(started, _)| {
+            (started.elapsed().as_secs_f64() / HOLD_DURATION.as_secs_f64()).min(1.0)
+        })
+    }
 
-    /// An optional label for when the timer goes off
-    #[arg(short)]
-    label: Option<String>,
-}
+    /// Silences a triggered timer, starting the next one in the queue. A lone timer restarts,
+    /// while the end of a queue exits unless `--repeat-whole-session` starts it over
+    pub fn dismiss(&mut self) {
+        if self.queue.len() <= 1 && !self.presets.is_empty() {
+            self.restart();
+            self.state = State::Standby;
+        } else if self.queue.len() <= 1 {
+            if let Some(sessions) = self.sessions {
+                if self.session >= sessions {
+                    self.running = false;
+                    return;
+                }
+                self.session += 1;
+            }
+            self.restart();
+        } else if !self.next_timer() {
+            if self.loop_queue {
+                self.first_timer();
+            } else {
+                self.running = false;
+            }
+        }
+    }
 
-fn parse_duration(arg: &str) -> std::result::Result<Duration, std::num::ParseIntError> {
-    let split_time_string: Vec<&str> = arg.split(":").collect();
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn restart(&mut self) {
+        let end_time = Local::now() + self.duration + Duration::seconds(1);
 
-    let mut time_in_seconds = 0;
-    for (i, num_string) in split_time_string.iter().enumerate() {
-        let modifier = match i {
-            0 => 60 * 60, // hours
-            1 => 60,      // minutes
-            2 => 1,       // seconds
-            _ => 1,
-        };
+        self.state = State::Running;
+        self.pre_pause_state = None;
+        self.paused_total = Duration::zero();
+        self.paused_at = None;
+        self.completed = false;
+        self.escalation_level = None;
+        self.label_ticks = 0;
+        self.chimed = false;
+        self.intervals_left = None;
+        self.pending_beeps = 0;
+        self.gentle_stage = false;
+        self.time_left = self.duration;
+        self.end_time = end_time;
 
-        let in_seconds = num_string.parse::<i64>()?;
-        time_in_seconds += in_seconds * modifier;
+        self.stop_sound();
     }
 
-    Ok(Duration::seconds(time_in_seconds))
-}
+    fn stop_sound(&mut self) {
+        if let Some(tx) = self.sender.take() {
+            let _result = tx.send(SoundCommand::Stop);
+        }
+    }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub enum State {
-    Running,
-    Paused,
-    Triggered,
-    Restart,
-}
+    /// Quits, or asks first when `--confirm-quit` is set and the timer is still counting
+    pub fn quit(&mut self) {
+        match self.state {
+            State::Running | State::Paused if self.confirm_quit => {
+                self.pre_quit_state = Some(self.state);
+                self.state = State::Quitting;
+            }
+            _ => {
+                self.running = false;
+            }
+        }
+    }
 
-pub struct App {
-    pub running: bool,
-    pub state: State,
-    pub pre_pause_state: Option<State>,
-    pub duration: Duration,
-    pub time_left: Duration,
-    pub end_time: DateTime<Local>,
-    pub colour: Color,
-    pub message: Option<String>,
-    pub sound_file: String,
-    pub sender: Option<Sender<()>>,
-}
+    pub fn cancel_quit(&mut self) {
+        self.state = self.pre_quit_state.map_or(State::Running, |s| s);
+        self.pre_quit_state = None;
+    }
 
-fn random_color() -> Color {
-    let mut rng = thread_rng();
+    /// Picks one of the presets while in standby, ignoring an index past the end
+    pub fn select_preset(&mut self, index: usize) {
+        let Some(&duration) = self.presets.get(index).filter(|_| self.state == State::Standby)
+        else {
+            return;
+        };
 
-    let index: u8 = rng.gen_range(0..15);
+        self.preset_index = index;
+        self.duration = duration;
+        self.time_left = duration;
+        if let Some(timer) = self.queue.get_mut(self.queue_index) {
+            timer.duration = duration;
+        }
+    }
 
-    match index {
-        1 => Color::Green,
-        2 => Color::Yellow,
-        3 => Color::Blue,
-        4 => Color::Magenta,
-        5 => Color::Cyan,
-        6 => Color::Gray,
-        7 => Color::DarkGray,
-        8 => Color::LightRed,
-        9 => Color::LightGreen,
-        10 => Color::LightYellow,
-        11 => Color::LightBlue,
-        12 => Color::LightMagenta,
-        13 => Color::LightCyan,
-        14 => Color::White,
-        _ => Color::Red, // 0 handled here
+    /// Starts counting down the preset picked in standby
+    pub fn arm(&mut self) {
+        if self.state == State::Standby {
+            self.restart();
+            self.play_start_chime();
+        }
     }
-}
 
-impl Default for App {
+    /// The presets listed one per line, with the one picked marked
+    fn preset_lines(&self) -> Vec<Spans<'_>> {
+        self.presets
+            .iter()
+            .enumerate()
+            .map(|(i, &duration)| {
+                let marker = if i == self.preset_index { '>' } else { ' ' };
+                let text = format!("{marker} {}. {}", i.saturating_add(1), format_hms(duration));
+
+                if i == self.preset_index {
+                    Spans::from(Span::styled(text, Style::default().add_modifier(Modifier::BOLD)))
+                } else {
+                    Spans::from(text)
+                }
+            })
+            .collect()
+    }
+
+    /// Begins the countdown of a waiting timer
     #[allow(clippy::arithmetic_side_effects)]
-    fn default() -> Self {
-        let duration = Duration::seconds(5);
-        let end_time = Local::now() + duration;
+    pub fn start(&mut self) {
+        self.state = State::Running;
+        self.start_time = None;
+        self.end_time = Local::now() + self.time_left;
+        self.play_start_chime();
+    }
 
-        Self {
-            running: true,
-            state: State::Running,
-            pre_pause_state: None,
-            duration,
-            time_left: duration,
-            end_time,
-            colour: random_color(),
-            message: None,
-            sound_file: String::from(""),
-            sender: None,
+    /// Starts a running countdown over from the time it shows now, for when it was held at the
+    /// full duration for `--grace` after launch
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn start_from_now(&mut self) {
+        if self.state == State::Running {
+            self.end_time = Local::now() + self.time_left;
         }
     }
-}
 
-impl App {
-    #[must_use]
-    pub fn new(args: Args) -> Self {
-        let end_time = Local::now() + args.time;
+    /// Plays the `--sound-start` chime for a countdown that began at launch, once the caller is
+    /// ready to start ticking. Timers that wait or need picking play it as they start
+    pub fn chime_if_running(&mut self) {
+        if self.state == State::Running {
+            self.play_start_chime();
+        }
+    }
 
-        Self {
-            duration: args.time,
-            time_left: args.time,
-            end_time,
-            message: args.label,
-            sound_file: args.sound,
-            ..Self::default()
+    /// Plays the `--sound-start` chime, if there is one
+    pub fn play_start_chime(&mut self) {
+        if let Some(sound_file) = &self.start_chime {
+            self.error = self
+                .chime(sound_file)
+                .err()
+                .map(|e| format!("Error playing sound: {e}"));
         }
     }
 
+    /// Adds time to the countdown, or takes it away for a negative amount. Never takes it below
+    /// one second, so a subtraction can't accidentally trigger the alarm
     #[allow(clippy::arithmetic_side_effects)]
-    pub fn tick(&mut self) {
-        match self.state {
-            State::Paused => {
-                self.end_time = Local::now() + self.time_left;
-            }
-            State::Running | State::Restart => {
-                self.time_left = self.end_time.signed_duration_since(Local::now());
+    pub fn add_time(&mut self, amount: Duration) {
+        if let State::Running | State::Paused = self.state {
+            let amount = amount.max(Duration::seconds(1) - self.time_left);
 
-                if self.time_left <= Duration::zero() {
-                    if let Err(e) = self.start_sound() {
-                        eprintln!("Error playing sound: {e}");
-                    };
+            self.time_left = self.time_left + amount;
+            self.end_time += amount;
+            self.last_adjustment = Some((amount, Instant::now()));
+        }
+    }
 
-                    self.state = State::Triggered;
-                }
+    /// Starts typing in a new duration for a countdown that is running or paused
+    pub fn edit_duration(&mut self) {
+        if let State::Running | State::Paused = self.state {
+            if !self.count_up {
+                self.input = Some(String::new());
+                self.input_error = None;
             }
-            State::Triggered => {
-                self.time_left = self.end_time.signed_duration_since(Local::now());
+        }
+    }
+
+    /// Sets the countdown to the duration typed in, or keeps the input open with the reason it
+    /// can't be used
+    pub fn submit_input(&mut self) {
+        let Some(input) = &self.input else {
+            return;
+        };
+
+        match parse_duration(input.trim()) {
+            Ok(duration) if duration > Duration::zero() => {
+                self.set_duration(duration);
+                self.input = None;
+                self.input_error = None;
             }
+            Ok(_) => self.input_error = Some("the duration must be longer than zero".into()),
+            Err(e) => self.input_error = Some(e),
         }
     }
 
-    #[allow(clippy::modulo_arithmetic, clippy::indexing_slicing)]
-    pub fn render<B: Backend>(&self, frame: &mut Frame<'_, B>) {
-        let seconds = self.time_left.num_seconds().abs() % 60;
-        let minutes = self.time_left.num_minutes().abs() % 60;
-        let hours = self.time_left.num_hours().abs();
+    /// Starts the countdown over from a new duration, leaving it running or paused as it was
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+        self.time_left = duration;
+        self.end_time = Local::now() + duration;
+        self.chimed = false;
+        self.intervals_left = None;
+        self.pending_beeps = 0;
+        log::info!("duration set to {}", format_hms(duration));
+    }
 
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(49),
-                    Constraint::Length(1),
-                    Constraint::Min(1),
-                ]
-                .as_ref(),
-            )
-            .split(frame.size());
+    /// Silences a timer that has gone off and sets it counting down again for the `--extend`
+    /// amount, keeping its original duration for the next restart
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn extend(&mut self) {
+        if self.state == State::Triggered {
+            self.stop_sound();
+            self.state = State::Running;
+            self.flash_ticks = 0;
+            self.escalation_level = None;
+            self.triggered_at = None;
+            self.time_left = self.extend_by;
+            self.end_time = Local::now() + self.extend_by;
+        }
+    }
 
-        frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Black)),
-            layout[0],
-        );
+    /// Picks a new random colour, unless one was chosen up front
+    pub fn recolour(&mut self) {
+        if !self.fixed_colour {
+            self.colour = random_color(&mut self.rng);
+        }
+    }
 
-        let time_prefix = if self.state == State::Triggered {
-            "-"
-        } else {
-            " "
-        };
-        let time_string = format!("{time_prefix}{hours:0>2}:{minutes:0>2}:{seconds:0>2}");
+    /// Leaves only the last few seconds of the countdown, to try out the alarm without waiting
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn skip_to_end(&mut self) {
+        if let State::Running | State::Paused = self.state {
+            self.time_left = Duration::seconds(SKIP_TO_END_SECONDS);
+            self.end_time = Local::now() + self.time_left;
+        }
+    }
 
-        frame.render_widget(
-            Paragraph::new(time_string)
-                .block(Block::default().borders(Borders::NONE))
-                .style(Style::default().fg(self.colour).bg(Color::Black))
-                .alignment(Alignment::Center),
-            layout[1],
-        );
+    /// Text for the most recent time adjustment, while it is still recent enough to show
+    #[must_use]
+    #[allow(clippy::modulo_arithmetic)]
+    pub fn adjustment_text(&self) -> Option<String> {
+        let (amount, at) = self.last_adjustment?;
+        if at.elapsed() > ADJUSTMENT_DISPLAY {
+            return None;
+        }
 
-        let widget = match self.state {
-            State::Paused | State::Restart | State::Triggered => {
-                let paragraph_string = match self.state {
-                    State::Paused => {
-                        " Paused"
-                    },
-                    State::Restart => {
-                        " Are you sure you want to restart the timer? (Press again to confirm, Esc/q to cancel)"
-                    },
-                    State::Triggered => {
-                        self.message.as_ref().map_or("", |message| message)
-                    }
-                    State::Running => "",
-                };
+        let sign = if amount < Duration::zero() { '−' } else { '+' };
+        let seconds = amount.num_seconds().abs();
 
-                Paragraph::new(paragraph_string)
-                    .block(Block::default().borders(Borders::NONE))
-                    .style(Style::default().fg(self.colour).bg(Color::Black))
-                    .alignment(Alignment::Center)
-            }
-            State::Running => {
-                Paragraph::new("").block(Block::default().style(Style::default().bg(Color::Black)))
-            }
-        };
+        Some(format!("{sign}{}:{:0>2}", seconds / 60, seconds % 60))
+    }
 
-        frame.render_widget(widget, layout[2]);
+    /// The volume limit from the quiet hours config, if they are in effect right now
+    fn quiet_volume(&self) -> Option<u8> {
+        self.quiet_hours
+            .filter(|quiet_hours| quiet_hours.contains(Local::now().time()))
+            .map(|quiet_hours| quiet_hours.volume)
     }
 
-    #[allow(clippy::arithmetic_side_effects)]
-    pub fn restart(&mut self) {
-        let end_time = Local::now() + self.duration + Duration::seconds(1);
+    /// The volume for the sink, from 0 to 1, once muting, quiet hours and escalation are
+    /// taken into account
+    #[allow(clippy::float_arithmetic)]
+    fn sink_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            let volume = self.quiet_volume().map_or(self.volume, |quiet| quiet.min(self.volume));
+            let volume = self.escalation_level.map_or(volume, |level| level.min(volume));
+            f32::from(volume) / 100.0
+        }
+    }
 
-        self.state = State::Running;
-        self.pre_pause_state = None;
-        self.time_left = self.duration;
-        self.end_time = end_time;
+    fn send_volume(&self) {
+        self.send_sound(SoundCommand::Volume(self.sink_volume()));
+    }
+
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(100);
+        self.send_volume();
+    }
+
+    /// Mutes or unmutes. A ringing alarm is paused while muted, and picks up where it left off
+    /// or starts over when unmuted, depending on `--restart-sound-on-resume`
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.send_volume();
 
+        if self.state != State::Triggered || self.sender.is_none() {
+            return;
+        }
+
+        if self.muted {
+            self.send_sound(SoundCommand::Pause);
+        } else if self.restart_sound_on_resume {
+            // A stopped sink won't play anything appended to it, so start a fresh one
+            self.stop_sound();
+            self.error = self
+                .start_sound()
+                .err()
+                .map(|e| format!("Error playing sound: {e}"));
+        } else {
+            self.send_sound(SoundCommand::Resume);
+        }
+    }
+
+    fn send_sound(&self, command: SoundCommand) {
         if let Some(tx) = &self.sender {
-            let _result = tx.send(());
+            let _result = tx.send(command);
+        }
+    }
+
+    /// The timer this run started with, in the form `--save-config` writes out
+    #[must_use]
+    pub fn settings(&self) -> TimerSettings {
+        let first = self.queue.first();
+
+        TimerSettings {
+            mode: Some(if self.count_up {
+                TimerMode::Stopwatch
+            } else {
+                TimerMode::Timer
+            }),
+            duration: first.map(|timer| format_hms(timer.duration)),
+            sound: first
+                .and_then(|timer| timer.sound.clone())
+                .or_else(|| Some(self.sound_file.clone()))
+                .filter(|sound| !sound.is_empty()),
+            label: first
+                .and_then(|timer| timer.label.clone())
+                .or_else(|| self.messages.first().cloned()),
+            colour: colour_name(self.colour),
+        }
+    }
+
+    #[must_use]
+    pub fn preferences(&self) -> Preferences {
+        Preferences {
+            volume: Some(self.volume),
+            muted: Some(self.muted),
+        }
+    }
+
+    /// Resets the overtime count back to zero while leaving the alarm playing
+    pub fn lap(&mut self) {
+        self.end_time = Local::now();
+        self.time_left = Duration::zero();
+    }
+
+    /// Reads every sound the timer might play into `sound_cache`. Files that can't be read yet
+    /// are left to report their error when they are due to play
+    fn cache_sounds(&mut self) {
+        let sounds = iter::once(&self.sound_file)
+            .chain(self.queue.iter().filter_map(|timer| timer.sound.as_ref()))
+            .chain(self.gentle.as_ref().map(|(sound, _)| sound))
+            .chain(self.escalation.as_ref().and_then(|escalation| escalation.sound.as_ref()))
+            .chain(&self.start_chime)
+            .chain(&self.tick_sound)
+            .filter(|sound| !sound.is_empty());
+
+        let mut cache = HashMap::new();
+        for sound in sounds {
+            if let Ok(bytes) = fs::read(sound) {
+                cache.insert(sound.clone(), bytes);
+            }
+        }
+        self.sound_cache = cache;
+    }
+
+    /// The contents of a sound file, from the copy taken at startup unless
+    /// `--repeat-from-file` asks for the latest. Reading it falls back to that copy if the file
+    /// has gone
+    fn sound_bytes(&self, sound_file: &str) -> Result<Vec<u8>> {
+        let cached = self.sound_cache.get(sound_file);
+        if let (false, Some(bytes)) = (self.reload_sound, cached) {
+            return Ok(bytes.clone());
+        }
+
+        match fs::read(sound_file) {
+            Ok(bytes) => Ok(bytes),
+            Err(e) => cached.cloned().ok_or_else(|| e.into()),
+        }
+    }
+
+    /// Plays the start of a sound once in the background, for cues that shouldn't interrupt
+    pub fn chime(&self, sound_file: &str) -> Result<()> {
+        if self.quiet_trigger {
+            return Ok(());
         }
+        let bytes = self.sound_bytes(sound_file)?;
+        let decoder = rodio::Decoder::new(Cursor::new(bytes))
+            .map_err(|e| describe_decoder_error(sound_file, e))?;
+        let volume = self.sink_volume();
+
+        thread::spawn(move || {
+            let Ok((_stream, handle)) = OutputStream::try_default() else {
+                return;
+            };
+            let Ok(sink) = Sink::try_new(&handle) else {
+                return;
+            };
+
+            sink.set_volume(volume);
+            sink.append(decoder.take_duration(CHIME_LENGTH));
+            sink.sleep_until_end();
+        });
 
-        self.sender = None;
+        Ok(())
     }
 
+    /// Plays the alarm, starting with the gentle sound while it has its turn
     pub fn start_sound(&mut self) -> Result<()> {
-        let file = File::open(self.sound_file.as_str())?;
+        let sound_file = match &self.gentle {
+            Some((gentle, _)) if self.gentle_stage => gentle.clone(),
+            _ => self.sound_file().to_owned(),
+        };
+        self.play_sound(&sound_file)
+    }
+
+    /// Starts the alarm sound on its own thread, unless `--quiet-trigger` keeps it silent
+    #[allow(clippy::arithmetic_side_effects)]
+    fn play_sound(&mut self, sound_file: &str) -> Result<()> {
+        if self.quiet_trigger {
+            return Ok(());
+        }
+
+        let bytes = self.sound_bytes(sound_file)?;
+
+        // Decode up front so an unplayable file is reported here rather than lost in the thread
+        let decoder = rodio::Decoder::new(Cursor::new(bytes.clone()))
+            .map_err(|e| describe_decoder_error(sound_file, e))?;
+
+        let offset = self.sound_offset;
+        if let Some(length) = decoder.total_duration().filter(|&length| offset >= length) {
+            return Err(format!(
+                "--sound-start-offset is {}s but {sound_file} is only {}s long",
+                offset.as_secs(),
+                length.as_secs()
+            )
+            .into());
+        }
 
         let (tx, rx) = std::sync::mpsc::channel();
 
         self.sender = Some(tx);
+        log::info!("playing {sound_file}");
 
-        thread::spawn(move || {
-            let (_stream, handle) = match OutputStream::try_default() {
-                Ok((stream, handle)) => (stream, handle),
-                Err(e) => {
-                    eprintln!("Could not open output stream: {e}");
-                    return;
+        let repeat = self.repeat;
+        let repeat_interval = self.repeat_interval;
+        let volume = self.sink_volume();
+        let fade = self.fade;
+        let reload = self.reload_sound;
+        let path = sound_file.to_owned();
+        let output = self.audio_output;
+
+        self.sound_thread = Some(thread::spawn(move || {
+            let Some((_output, sink)) = open_sink(output) else { return };
+
+            sink.pause();
+            sink.set_volume(volume);
+
+            let decode = || {
+                let bytes = if reload {
+                    fs::read(&path).unwrap_or_else(|e| {
+                        log::warn!("could not read {path} again, playing it as it was: {e}");
+                        bytes.clone()
+                    })
+                } else {
+                    bytes.clone()
+                };
+
+                match rodio::Decoder::new(Cursor::new(bytes)) {
+                    Ok(decoder) => Some(decoder.skip_duration(offset)),
+                    Err(e) => {
+                        log::error!("could not create decoder from file: {e}");
+                        None
+                    }
                 }
             };
 
-            let sink = match Sink::try_new(&handle) {
-                Ok(sink) => sink,
-                Err(e) => {
-                    eprintln!("Could not create Sink in sound thread: {e}");
-                    return;
+            // Looping the decoded sound would never look at the file again, so reloading plays
+            // it through back to back instead
+            if repeat.is_none() && repeat_interval.is_none() && !reload {
+                let Some(decoder) = decode() else { return };
+
+                if fade {
+                    sink.append(decoder.repeat_infinite().fade_in(FADE_IN));
+                } else {
+                    sink.append(decoder.repeat_infinite());
                 }
-            };
+                sink.play();
 
-            sink.pause();
+                while !stop_requested(&rx, &sink, POLL_INTERVAL) {}
+                sink.stop();
+                return;
+            }
 
-            let decoder = match rodio::Decoder::new(BufReader::new(file)) {
-                Ok(decoder) => decoder,
-                Err(e) => {
-                    eprintln!("Could not create decoder from file: {e}");
-                    return;
+            let mut plays = 0;
+            while repeat.is_none_or(|count| plays < count) {
+                if plays > 0 && stop_requested(&rx, &sink, repeat_interval.unwrap_or_default()) {
+                    break;
                 }
-            };
 
-            sink.append(
-                decoder
-                    .repeat_infinite()
-                    .fade_in(std::time::Duration::from_millis(500)),
-            );
+                let Some(decoder) = decode() else { return };
 
-            sink.play();
+                if fade {
+                    sink.append(decoder.fade_in(FADE_IN));
+                } else {
+                    sink.append(decoder);
+                }
+                sink.play();
 
-            loop {
-                match rx.try_recv() {
-                    Ok(_) | Err(TryRecvError::Disconnected) => {
+                while !sink.empty() {
+                    if stop_requested(&rx, &sink, POLL_INTERVAL) {
                         sink.stop();
-                        break;
+                        return;
                     }
-                    Err(TryRecvError::Empty) => {}
                 }
+
+                plays += 1;
             }
-        });
+        }));
 
         Ok(())
     }
 }
+
+/// Formats decodable with rodio's default features, which is what this build uses
+const SUPPORTED_FORMATS: &str = "WAV, FLAC, Ogg Vorbis and MP3";
+
+fn describe_decoder_error(path: &str, e: DecoderError) -> String {
+    match e {
+        DecoderError::UnrecognizedFormat => {
+            format!("{path} is not in a supported audio format, this build can play {SUPPORTED_FORMATS}")
+        }
+        #[allow(unreachable_patterns)]
+        other => format!("could not decode {path}: {other}"),
+    }
+}
+
+/// Waits up to `timeout` for the signal to stop the alarm, applying any volume changes, pauses
+/// and resumes sent in the meantime
+#[allow(clippy::arithmetic_side_effects)]
+fn stop_requested(rx: &Receiver<SoundCommand>, sink: &Sink, timeout: std::time::Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(SoundCommand::Volume(volume)) => sink.set_volume(volume),
+            Ok(SoundCommand::Pause) => sink.pause(),
+            Ok(SoundCommand::Resume) => sink.play(),
+            Ok(SoundCommand::Stop) | Err(RecvTimeoutError::Disconnected) => return true,
+            Err(RecvTimeoutError::Timeout) => return false,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::app::Result;
+use std::process::{self, Child, Command, Stdio};
+
+/// Stops the system going to sleep for as long as it is held, by running the platform's sleep
+/// inhibitor alongside the timer. The inhibitor also watches this process, so it goes away
+/// even if the timer exits without dropping it
+pub struct KeepAwake {
+    inhibitor: Child,
+}
+
+impl KeepAwake {
+    pub fn start() -> Result<Self> {
+        let mut command = inhibitor_command()?;
+        let program = command.get_program().to_string_lossy().into_owned();
+
+        let inhibitor = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("could not keep the system awake with {program}: {e}"))?;
+
+        Ok(Self { inhibitor })
+    }
+}
+
+impl Drop for KeepAwake {
+    fn drop(&mut self) {
+        let _result = self.inhibitor.kill();
+        let _result = self.inhibitor.wait();
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn inhibitor_command() -> Result<Command> {
+    let mut command = Command::new("systemd-inhibit");
+    command.args([
+        "--what=sleep:idle",
+        "--who=cli-timer",
+        "--why=Timer running",
+        "--mode=block",
+        "tail",
+        "-f",
+        "/dev/null",
+        &format!("--pid={}", process::id()),
+    ]);
+    Ok(command)
+}
+
+#[cfg(target_os = "macos")]
+fn inhibitor_command() -> Result<Command> {
+    let mut command = Command::new("caffeinate");
+    command.args(["-i", "-d", "-w", &process::id().to_string()]);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn inhibitor_command() -> Result<Command> {
+    Err("--keep-awake is only supported on Linux and macOS".into())
+}
@@ -0,0 +1,62 @@
+use crate::app::{App, Result};
+use crate::fifo::StatusFifo;
+use crate::session::SessionFile;
+use crate::socket::StatusSocket;
+use std::path::Path;
+
+/// The ways other programs follow or change a running timer, `--session`, `--socket` and
+/// `--fifo`, kept up to date every tick whichever way the timer is shown
+#[derive(Default)]
+pub struct SideChannels {
+    session: Option<SessionFile>,
+    socket: Option<StatusSocket>,
+    fifo: Option<StatusFifo>,
+    /// Whether each one's last update failed, so a failure is logged once rather than on every
+    /// tick
+    failing: [bool; 3],
+}
+
+impl SideChannels {
+    pub fn open(
+        session: Option<Option<&Path>>,
+        socket: Option<&Path>,
+        fifo: Option<&Path>,
+    ) -> Result<Self> {
+        Ok(Self {
+            session: session.map(SessionFile::create).transpose()?,
+            socket: socket.map(StatusSocket::bind).transpose()?,
+            fifo: fifo.map(StatusFifo::create).transpose()?,
+            failing: [false; 3],
+        })
+    }
+
+    /// Brings each channel up to date with the timer. A channel that can't be updated is logged
+    /// rather than stopping the timer
+    pub fn update(&mut self, app: &mut App) {
+        let [session_failing, socket_failing, fifo_failing] = &mut self.failing;
+
+        if let Some(session) = &mut self.session {
+            log_failure(session_failing, "the session file", session.sync(app));
+        }
+
+        if let Some(socket) = &self.socket {
+            log_failure(socket_failing, "the status socket", socket.update(app));
+        }
+
+        if let Some(fifo) = &mut self.fifo {
+            log_failure(fifo_failing, "the status fifo", fifo.update(app));
+        }
+    }
+}
+
+/// Logs a channel that couldn't be kept up to date, only the first time in a row it fails
+fn log_failure(failing: &mut bool, what: &str, result: Result<()>) {
+    match result {
+        Ok(()) => *failing = false,
+        Err(e) if !*failing => {
+            log::warn!("could not update {what}: {e}");
+            *failing = true;
+        }
+        Err(_) => {}
+    }
+}
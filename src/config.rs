@@ -0,0 +1,186 @@
+use crate::app::Result;
+use crate::keys::KeyMap;
+use chrono::NaiveTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub keys: KeyMap,
+    pub preferences: Preferences,
+    pub timer: TimerSettings,
+    /// Named sets of timer settings, e.g. `[profile.work]`, chosen with `--profile`
+    pub profile: BTreeMap<String, TimerSettings>,
+    #[serde(rename = "quiet-hours")]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// Settings changed while the timer runs, which `--remember` writes back on exit
+#[derive(Deserialize, Serialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Preferences {
+    pub volume: Option<u8>,
+    pub muted: Option<bool>,
+}
+
+/// Whether a saved timer counts down or up
+#[derive(Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerMode {
+    Timer,
+    Stopwatch,
+}
+
+/// The timer to run when none is given on the command line, as written by `--save-config`
+#[derive(Deserialize, Serialize, Default, Clone, PartialEq, Eq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimerSettings {
+    pub mode: Option<TimerMode>,
+    /// In the same formats as `-t`, e.g. 25m or 00:25:00
+    pub duration: Option<String>,
+    pub sound: Option<String>,
+    pub label: Option<String>,
+    /// One of the colour names `--warn-color` takes, e.g. light-blue
+    pub colour: Option<String>,
+}
+
+impl TimerSettings {
+    /// These settings, falling back to `defaults` for any left unset
+    #[must_use]
+    pub fn or(self, defaults: Self) -> Self {
+        Self {
+            mode: self.mode.or(defaults.mode),
+            duration: self.duration.or(defaults.duration),
+            sound: self.sound.or(defaults.sound),
+            label: self.label.or(defaults.label),
+            colour: self.colour.or(defaults.colour),
+        }
+    }
+}
+
+/// A daily window, e.g. overnight, when the alarm plays quieter or not at all
+#[derive(Deserialize, Copy, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+    #[serde(deserialize_with = "time_of_day")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "time_of_day")]
+    pub end: NaiveTime,
+    /// Alarm volume as a percentage during quiet hours, where 0 leaves only the visual alarm
+    #[serde(default)]
+    pub volume: u8,
+}
+
+impl QuietHours {
+    /// Whether the time falls in the window, which wraps past midnight when it ends before
+    /// it starts
+    #[must_use]
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+fn time_of_day<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<NaiveTime, D::Error> {
+    let time = String::deserialize(deserializer)?;
+
+    NaiveTime::parse_from_str(&time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&time, "%H:%M:%S"))
+        .map_err(|e| serde::de::Error::custom(format!("invalid time {time}: {e}")))
+}
+
+impl Config {
+    /// Loads the config from the given path, or from the default location if it exists
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("could not read config {}: {e}", path.display()))?;
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| format!("invalid config {}: {e}", path.display()))?;
+
+        config.keys.validate()?;
+
+        Ok(config)
+    }
+
+    /// Layers the named profile over the `[timer]` settings
+    pub fn use_profile(&mut self, name: &str) -> Result<()> {
+        let Some(profile) = self.profile.remove(name) else {
+            let available: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+            return Err(if available.is_empty() {
+                format!("no profile named {name}, the config doesn't define any")
+            } else {
+                format!("no profile named {name}, available profiles: {}", available.join(", "))
+            }
+            .into());
+        };
+
+        self.timer = profile.or(std::mem::take(&mut self.timer));
+        Ok(())
+    }
+
+    /// Writes the preferences into the config file, leaving its other sections as they were
+    pub fn save_preferences(path: Option<&Path>, preferences: &Preferences) -> Result<()> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => default_path().ok_or("could not find a config directory")?,
+        };
+
+        let mut table = if path.exists() {
+            fs::read_to_string(&path)?.parse::<toml::Table>()?
+        } else {
+            toml::Table::new()
+        };
+
+        table.insert(
+            String::from("preferences"),
+            toml::Value::try_from(preferences)?,
+        );
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string(&table)?)?;
+
+        Ok(())
+    }
+
+    /// Writes a config holding just the given timer and preferences, which `--config` can load
+    /// to run the same timer again
+    pub fn save(path: &Path, timer: &TimerSettings, preferences: &Preferences) -> Result<()> {
+        let mut table = toml::Table::new();
+        table.insert(String::from("timer"), toml::Value::try_from(timer)?);
+        table.insert(
+            String::from("preferences"),
+            toml::Value::try_from(preferences)?,
+        );
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&table)?)
+            .map_err(|e| format!("could not write config {}: {e}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cli-timer").join("config.toml"))
+}
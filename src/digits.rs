@@ -0,0 +1,147 @@
+use crate::app::Result;
+use clap::ValueEnum;
+use std::io::{ErrorKind, Write};
+
+/// Styles for drawing the clock in large characters
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Font {
+    /// Double width solid blocks, 5 rows tall
+    Block,
+    /// Box drawing lines, 3 rows tall
+    Thin,
+    /// Single width solid blocks, 5 rows tall
+    #[value(name = "3x5")]
+    Small,
+    /// Seven segment display, 3 rows tall
+    #[value(name = "7-segment")]
+    Segment,
+}
+
+/// The 3x5 bitmaps the block fonts are drawn from
+fn bitmap(c: char) -> Option<[&'static str; 5]> {
+    Some(match c {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        ':' => [" ", "#", " ", "#", " "],
+        '+' => ["   ", " # ", "###", " # ", "   "],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        ' ' => ["   ", "   ", "   ", "   ", "   "],
+        _ => return None,
+    })
+}
+
+fn thin(c: char) -> Option<[&'static str; 3]> {
+    Some(match c {
+        '0' => ["┌─┐", "│ │", "└─┘"],
+        '1' => ["  ╷", "  │", "  ╵"],
+        '2' => ["╶─┐", "┌─┘", "└─╴"],
+        '3' => ["╶─┐", " ─┤", "╶─┘"],
+        '4' => ["╷ ╷", "└─┤", "  ╵"],
+        '5' => ["┌─╴", "└─┐", "╶─┘"],
+        '6' => ["┌─╴", "├─┐", "└─┘"],
+        '7' => ["╶─┐", "  │", "  ╵"],
+        '8' => ["┌─┐", "├─┤", "└─┘"],
+        '9' => ["┌─┐", "└─┤", "╶─┘"],
+        ':' => [" ", ":", " "],
+        '+' => [" ╷ ", "╶┼╴", " ╵ "],
+        '-' => ["   ", "╶─╴", "   "],
+        ' ' => ["   ", "   ", "   "],
+        _ => return None,
+    })
+}
+
+fn segment(c: char) -> Option<[&'static str; 3]> {
+    Some(match c {
+        '0' => [" _ ", "| |", "|_|"],
+        '1' => ["   ", "  |", "  |"],
+        '2' => [" _ ", " _|", "|_ "],
+        '3' => [" _ ", " _|", " _|"],
+        '4' => ["   ", "|_|", "  |"],
+        '5' => [" _ ", "|_ ", " _|"],
+        '6' => [" _ ", "|_ ", "|_|"],
+        '7' => [" _ ", "  |", "  |"],
+        '8' => [" _ ", "|_|", "|_|"],
+        '9' => [" _ ", "|_|", " _|"],
+        ':' => [" ", ":", " "],
+        '+' => ["   ", "_|_", " | "],
+        '-' => ["   ", "___", "   "],
+        ' ' => ["   ", "   ", "   "],
+        _ => return None,
+    })
+}
+
+impl Font {
+    /// Number of rows each character takes up
+    #[must_use]
+    pub const fn height(self) -> usize {
+        match self {
+            Self::Block | Self::Small => 5,
+            Self::Thin | Self::Segment => 3,
+        }
+    }
+
+    fn glyph(self, c: char) -> Option<Vec<String>> {
+        match self {
+            Self::Block => bitmap(c).map(|rows| {
+                rows.iter()
+                    .map(|row| row.replace('#', "██").replace(' ', "  "))
+                    .collect()
+            }),
+            Self::Small => bitmap(c).map(|rows| rows.iter().map(|row| row.replace('#', "█")).collect()),
+            Self::Thin => thin(c).map(|rows| rows.iter().map(|&row| String::from(row)).collect()),
+            Self::Segment => {
+                segment(c).map(|rows| rows.iter().map(|&row| String::from(row)).collect())
+            }
+        }
+    }
+
+    /// Draws the text in this font, one string per row. Characters without a glyph are skipped
+    #[must_use]
+    pub fn render(self, text: &str) -> Vec<String> {
+        let mut lines = vec![String::new(); self.height()];
+
+        for glyph in text.chars().filter_map(|c| self.glyph(c)) {
+            for (line, row) in lines.iter_mut().zip(glyph) {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&row);
+            }
+        }
+
+        lines
+    }
+}
+
+/// Writes every font with a sample, for `--list-fonts`. A reader that stops early, like
+/// `head`, ends the list quietly
+pub fn print_fonts(out: &mut impl Write) -> Result<()> {
+    match write_fonts(out) {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn write_fonts(out: &mut impl Write) -> std::io::Result<()> {
+    for font in Font::value_variants() {
+        if let Some(value) = font.to_possible_value() {
+            writeln!(out, "{}", value.get_name())?;
+        }
+
+        for line in font.render("12:34") {
+            writeln!(out, "  {line}")?;
+        }
+
+        writeln!(out)?;
+    }
+
+    out.flush()
+}
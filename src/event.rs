@@ -1,15 +1,64 @@
 use crate::app::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
-use std::sync::mpsc;
+use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// How often, in milliseconds, the timer is ticked to update the clock
+pub const TICK_RATE: u64 = 250;
+
 #[derive(Clone, Copy)]
 pub enum Event {
     Tick,
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
+    /// Ctrl-Z was pressed or the process was sent SIGTSTP, so the terminal should be handed
+    /// back before the process stops
+    Suspend,
+    /// The process was sent SIGUSR1, asking for the timer to be paused or resumed
+    TogglePause,
+}
+
+/// Whether the key is Ctrl-Z, which raw mode delivers as a key instead of suspending
+fn is_suspend_key(key: &KeyEvent) -> bool {
+    cfg!(unix)
+        && key.modifiers == KeyModifiers::CONTROL
+        && matches!(key.code, KeyCode::Char('z' | 'Z'))
+}
+
+/// Sets a flag whenever the process is sent SIGTSTP, so the terminal can be restored before it
+/// actually stops
+#[allow(clippy::expect_used)]
+fn suspend_flag() -> Arc<AtomicBool> {
+    let flag = Arc::new(AtomicBool::new(false));
+
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGTSTP, Arc::clone(&flag))
+        .expect("unable to listen for SIGTSTP");
+
+    flag
+}
+
+/// Sets a flag whenever the process is sent SIGUSR1, which pauses or resumes the timer. Never
+/// set on platforms without the signal
+pub fn pause_flag() -> Result<Arc<AtomicBool>> {
+    let flag = Arc::new(AtomicBool::new(false));
+
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGUSR1, Arc::clone(&flag))?;
+
+    Ok(flag)
+}
+
+/// Stops the process the way Ctrl-Z does outside raw mode, returning once it is continued
+/// with `fg`
+pub fn suspend_process() -> Result<()> {
+    #[cfg(unix)]
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+
+    Ok(())
 }
 
 #[allow(dead_code)]
@@ -25,6 +74,8 @@ impl Handler {
     pub fn new(tick_rate: u64) -> Self {
         let tick_rate = Duration::from_millis(tick_rate);
         let (sender, receiver) = mpsc::channel();
+        let suspend_requested = suspend_flag();
+        let pause_requested = pause_flag().expect("unable to listen for SIGUSR1");
         let handler = {
             let sender = sender.clone();
             thread::spawn(move || {
@@ -36,14 +87,31 @@ impl Handler {
 
                     if event::poll(timeout).expect("no events available") {
                         match event::read().expect("unable to read event") {
+                            CrosstermEvent::Key(e) if is_suspend_key(&e) => {
+                                log::info!("suspending on Ctrl-Z");
+                                sender.send(Event::Suspend)
+                            }
                             CrosstermEvent::Key(e) => sender.send(Event::Key(e)),
                             CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                            CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
+                            CrosstermEvent::Resize(w, h) => {
+                                log::debug!("terminal resized to {w}x{h}");
+                                sender.send(Event::Resize(w, h))
+                            }
                             _ => Ok(()),
                         }
                         .expect("failed to send terminal event");
                     }
 
+                    if suspend_requested.swap(false, Ordering::Relaxed) {
+                        log::info!("suspending on SIGTSTP");
+                        sender.send(Event::Suspend).expect("failed to send suspend event");
+                    }
+
+                    if pause_requested.swap(false, Ordering::Relaxed) {
+                        log::info!("toggling pause on SIGUSR1");
+                        sender.send(Event::TogglePause).expect("failed to send pause event");
+                    }
+
                     if last_tick.elapsed() >= tick_rate {
                         sender.send(Event::Tick).expect("failed to send tick event");
                         last_tick = Instant::now();
@@ -61,4 +129,9 @@ impl Handler {
     pub fn next(&self) -> Result<Event> {
         Ok(self.receiver.recv()?)
     }
+
+    /// Every event as it arrives, blocking until the next one
+    pub fn iter(&self) -> impl Iterator<Item = Event> + '_ {
+        self.receiver.iter()
+    }
 }
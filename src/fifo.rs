@@ -0,0 +1,90 @@
+use crate::app::{App, Result};
+use std::fs::File;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes the seconds left to a named pipe every tick, for status bars that would rather read
+/// a file than speak to a socket. Nothing is written while no one is reading
+pub struct StatusFifo {
+    path: PathBuf,
+    reader: Option<File>,
+}
+
+impl StatusFifo {
+    #[cfg(unix)]
+    pub fn create(path: &Path) -> Result<Self> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::FileTypeExt;
+
+        match path.metadata() {
+            Ok(metadata) if metadata.file_type().is_fifo() => {}
+            Ok(_) => return Err(format!("{} exists and is not a FIFO", path.display()).into()),
+            Err(_) => {
+                let c_path = CString::new(path.as_os_str().as_bytes())?;
+                // SAFETY: `c_path` is a valid nul-terminated string that outlives the call
+                if unsafe { libc::mkfifo(c_path.as_ptr(), 0o644) } != 0 {
+                    let error = std::io::Error::last_os_error();
+                    return Err(format!("could not create {}: {error}", path.display()).into());
+                }
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            reader: None,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn create(_path: &Path) -> Result<Self> {
+        Err("--fifo is only supported on Unix".into())
+    }
+
+    pub fn update(&mut self, app: &App) -> Result<()> {
+        if self.reader.is_none() {
+            self.reader = self.open()?;
+        }
+
+        let seconds = app.time_left.num_seconds();
+        if let Some(reader) = &mut self.reader {
+            match writeln!(reader, "{seconds}") {
+                Ok(()) => {}
+                // A full pipe means the reader is behind, so this second is skipped
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                // The reader went away, so wait for the next one
+                Err(_) => self.reader = None,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Opens the write end without waiting, giving `None` while no one has it open to read
+    #[cfg(unix)]
+    fn open(&self) -> Result<Option<File>> {
+        use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        match OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&self.path)
+        {
+            Ok(file) => Ok(Some(file)),
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+            Err(e) => Err(format!("could not open {}: {e}", self.path.display()).into()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn open(&self) -> Result<Option<File>> {
+        Ok(None)
+    }
+}
+
+impl Drop for StatusFifo {
+    fn drop(&mut self) {
+        let _result = std::fs::remove_file(&self.path);
+    }
+}
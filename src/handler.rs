@@ -1,47 +1,322 @@
 use crate::app::{App, Result, State};
+use crate::event::Event;
+use crate::keys::Action;
+use chrono::Duration;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+const VOLUME_STEP: u8 = 10;
+
+/// A key in the footer: one of the configurable actions, or a key with a fixed meaning in
+/// that state
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HintKey {
+    Bound(Action),
+    Fixed(&'static str),
+}
+
+/// The keys that do something in each state and what they do there, most useful first. Key
+/// presses for actions missing from a state's list are ignored, so the footer can't leave any
+/// out
+#[must_use]
+pub const fn key_hints(state: State) -> &'static [(HintKey, &'static str)] {
+    use HintKey::{Bound, Fixed};
+
+    match state {
+        State::Waiting => &[
+            (Bound(Action::Pause), "start now"),
+            (Bound(Action::Quit), "quit"),
+            (Bound(Action::Mute), "mute"),
+            (Bound(Action::VolumeDown), "vol-"),
+            (Bound(Action::VolumeUp), "vol+"),
+            (Bound(Action::Elapsed), "elapsed"),
+            (Bound(Action::Recolour), "colour"),
+            (Bound(Action::Info), "info"),
+        ],
+        State::Standby => &[
+            (Bound(Action::Pause), "start"),
+            (Fixed("↑/↓"), "pick"),
+            (Fixed("1-9"), "pick"),
+            (Fixed("Enter"), "start"),
+            (Bound(Action::Quit), "quit"),
+            (Bound(Action::Mute), "mute"),
+            (Bound(Action::VolumeDown), "vol-"),
+            (Bound(Action::VolumeUp), "vol+"),
+            (Bound(Action::Elapsed), "elapsed"),
+            (Bound(Action::Recolour), "colour"),
+            (Bound(Action::Info), "info"),
+        ],
+        State::Running => &[
+            (Bound(Action::Pause), "pause"),
+            (Bound(Action::Restart), "restart"),
+            (Bound(Action::AddTime), "+1m"),
+            (Bound(Action::SubtractTime), "-1m"),
+            (Bound(Action::Edit), "set time"),
+            (Bound(Action::Quit), "quit"),
+            (Bound(Action::Mute), "mute"),
+            (Bound(Action::VolumeDown), "vol-"),
+            (Bound(Action::VolumeUp), "vol+"),
+            (Bound(Action::Elapsed), "elapsed"),
+            (Bound(Action::Recolour), "colour"),
+            (Bound(Action::Info), "info"),
+        ],
+        State::Paused => &[
+            (Bound(Action::Pause), "resume"),
+            (Bound(Action::AddTime), "+1m"),
+            (Bound(Action::SubtractTime), "-1m"),
+            (Bound(Action::Edit), "set time"),
+            (Bound(Action::Quit), "quit"),
+            (Bound(Action::Mute), "mute"),
+            (Bound(Action::VolumeDown), "vol-"),
+            (Bound(Action::VolumeUp), "vol+"),
+            (Bound(Action::Elapsed), "elapsed"),
+            (Bound(Action::Recolour), "colour"),
+            (Bound(Action::Info), "info"),
+        ],
+        State::Triggered => &[
+            (Bound(Action::Pause), "dismiss"),
+            (Bound(Action::Restart), "restart"),
+            (Bound(Action::Extend), "extend"),
+            (Bound(Action::Lap), "lap"),
+            (Bound(Action::Mute), "mute"),
+            (Bound(Action::Quit), "quit"),
+            (Bound(Action::VolumeDown), "vol-"),
+            (Bound(Action::VolumeUp), "vol+"),
+            (Bound(Action::Elapsed), "elapsed"),
+            (Bound(Action::Recolour), "colour"),
+            (Bound(Action::Info), "info"),
+        ],
+        State::Restart => &[
+            (Bound(Action::Restart), "confirm"),
+            (Bound(Action::Quit), "cancel"),
+        ],
+        State::Quitting => &[
+            (Fixed("y"), "quit"),
+            (Fixed("n"), "stay"),
+            (Bound(Action::Quit), "quit"),
+        ],
+    }
+}
+
+/// Whether the action does anything in this state, going by `key_hints`
+#[must_use]
+pub fn handles(state: State, action: Action) -> bool {
+    key_hints(state)
+        .iter()
+        .any(|&(key, _)| key == HintKey::Bound(action))
+}
+
+/// Pauses a running timer or resumes a paused one. Other states are left alone, so a signal
+/// can't start, dismiss or confirm anything
+pub fn toggle_pause(app: &mut App) {
+    match app.state {
+        State::Running => {
+            app.pause();
+        }
+        State::Paused => {
+            app.resume();
+        }
+        State::Waiting | State::Standby | State::Triggered | State::Restart | State::Quitting => {}
+    }
+}
+
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> Result<()> {
-    match key_event.code {
-        // Exit application on `ESC` or `q`
-        KeyCode::Esc | KeyCode::Char('q') => match app.state {
+    // Exit application on `Ctrl-C`
+    if key_event.modifiers == KeyModifiers::CONTROL
+        && matches!(key_event.code, KeyCode::Char('c' | 'C'))
+    {
+        app.running = false;
+        return Ok(());
+    }
+
+    if app.state == State::Quitting {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('n' | 'N') => {
+                app.cancel_quit();
+            }
+            KeyCode::Char('y' | 'Y') => {
+                app.running = false;
+            }
+            KeyCode::Char(key) if app.keys.action(key) == Some(Action::Quit) => {
+                app.running = false;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.show_info {
+        match key_event.code {
+            KeyCode::Esc => {
+                app.show_info = false;
+            }
+            KeyCode::Char(key) if app.keys.action(key) == Some(Action::Info) => {
+                app.show_info = false;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if let Some(input) = &mut app.input {
+        match key_event.code {
+            KeyCode::Esc => {
+                app.input = None;
+                app.input_error = None;
+            }
+            KeyCode::Enter => {
+                app.submit_input();
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                input.push(c);
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.debug_keys && matches!(key_event.code, KeyCode::Char('t' | 'T')) {
+        app.skip_to_end();
+        return Ok(());
+    }
+
+    if app.state == State::Standby {
+        match key_event.code {
+            KeyCode::Up => {
+                app.select_preset(app.preset_index.saturating_sub(1));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                app.select_preset(app.preset_index.saturating_add(1));
+                return Ok(());
+            }
+            KeyCode::Char(digit @ '1'..='9') => {
+                let index = digit.to_digit(10).unwrap_or(1).saturating_sub(1);
+                app.select_preset(usize::try_from(index).unwrap_or(0));
+                return Ok(());
+            }
+            KeyCode::Enter => {
+                app.arm();
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+
+    let action = match key_event.code {
+        // `ESC` always behaves like the quit key
+        KeyCode::Esc => Some(Action::Quit),
+        KeyCode::Char(key) => app.keys.action(key),
+        _ => None,
+    }
+    .filter(|&action| handles(app.state, action));
+
+    match action {
+        Some(Action::Quit) => match app.state {
             State::Restart => {
                 app.state = State::Running;
             }
             _ => {
-                app.running = false;
+                app.quit();
             }
         },
-        // Exit application on `Ctrl-C`
-        KeyCode::Char('c' | 'C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.running = false;
+        Some(Action::Pause) => match app.state {
+            State::Waiting => {
+                app.start();
             }
-        }
-        KeyCode::Char(' ') => match app.state {
-            State::Running => {
-                app.pre_pause_state = Some(app.state);
-                app.state = State::Paused;
+            State::Standby => {
+                app.arm();
+            }
+            State::Running | State::Paused => {
+                toggle_pause(app);
             }
-            State::Paused => {
-                app.state = app.pre_pause_state.map_or(State::Running, |s| s);
-                app.pre_pause_state = None;
+            State::Triggered if app.hold_to_dismiss => {
+                app.hold_dismiss();
             }
             State::Triggered => {
-                app.restart();
+                app.dismiss();
             }
-            State::Restart => {}
+            State::Restart | State::Quitting => {}
         },
-        KeyCode::Char('r' | 'R') => match app.state {
+        Some(Action::Restart) => match app.state {
             State::Running => {
                 app.state = State::Restart;
             }
+            State::Triggered if app.hold_to_dismiss => {
+                if app.held() {
+                    app.restart();
+                }
+            }
             State::Restart | State::Triggered => {
                 app.restart();
             }
-            State::Paused => {}
+            State::Waiting | State::Standby | State::Paused | State::Quitting => {}
         },
-        _ => {}
+        Some(Action::AddTime) => {
+            app.add_time(Duration::minutes(1));
+        }
+        Some(Action::SubtractTime) => {
+            app.add_time(Duration::minutes(-1));
+        }
+        Some(Action::Lap) => {
+            app.lap();
+        }
+        Some(Action::VolumeUp) => {
+            app.set_volume(app.volume.saturating_add(VOLUME_STEP));
+        }
+        Some(Action::VolumeDown) => {
+            app.set_volume(app.volume.saturating_sub(VOLUME_STEP));
+        }
+        // Muting or snoozing the alarm silences it as surely as dismissing does, so under
+        // `--hold-to-dismiss` they need holding down too
+        Some(Action::Mute | Action::Extend)
+            if app.state == State::Triggered && app.hold_to_dismiss && !app.held() => {}
+        Some(Action::Mute) => {
+            app.toggle_mute();
+        }
+        Some(Action::Extend) => {
+            app.extend();
+        }
+        Some(Action::Recolour) => {
+            app.recolour();
+        }
+        Some(Action::Info) => {
+            app.show_info = true;
+        }
+        Some(Action::Elapsed) => {
+            app.show_elapsed = !app.show_elapsed;
+        }
+        Some(Action::Edit) => {
+            app.edit_duration();
+        }
+        None => {}
     }
     Ok(())
 }
+
+/// Feeds events to the app until it stops running or they run out, calling `after` with each
+/// event once the app has handled it
+pub fn run_loop<E, F>(app: &mut App, events: E, mut after: F) -> Result<()>
+where
+    E: IntoIterator<Item = Event>,
+    F: FnMut(&mut App, Event) -> Result<()>,
+{
+    for event in events {
+        match event {
+            Event::Tick => app.tick(),
+            Event::Key(key_event) => handle_key_events(key_event, app)?,
+            Event::TogglePause => toggle_pause(app),
+            Event::Mouse(_) | Event::Resize(_, _) | Event::Suspend => {}
+        }
+
+        after(app, event)?;
+
+        if !app.running {
+            break;
+        }
+    }
+
+    Ok(())
+}
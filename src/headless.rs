@@ -0,0 +1,265 @@
+use crate::app::{App, Result, State};
+use crate::channels::SideChannels;
+use crate::event::{self, Event, Handler};
+use crate::handler::{run_loop, toggle_pause};
+use crossterm::terminal;
+use serde::Serialize;
+use signal_hook::consts::SIGINT;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct Status<'a> {
+    event: &'static str,
+    state: &'static str,
+    label: Option<&'a str>,
+    duration_seconds: i64,
+    remaining_seconds: i64,
+    percent_complete: f64,
+    end_time: String,
+    error: Option<&'a str>,
+}
+
+impl<'a> Status<'a> {
+    #[allow(clippy::float_arithmetic)]
+    fn new(event: &'static str, app: &'a App) -> Self {
+        let state = match app.state {
+            State::Waiting => "waiting",
+            State::Standby => "standby",
+            State::Running => "running",
+            State::Paused => "paused",
+            State::Triggered => "triggered",
+            State::Restart => "restart",
+            State::Quitting => "quitting",
+        };
+
+        Self {
+            event,
+            state,
+            label: app.label(),
+            duration_seconds: app.duration.num_seconds(),
+            remaining_seconds: app.time_left.num_seconds(),
+            percent_complete: (app.percent_complete() * 10.0).round() / 10.0,
+            end_time: app.projected_end_time().to_rfc3339(),
+            error: app.error.as_deref(),
+        }
+    }
+}
+
+/// The timer's current status as a single line of JSON
+pub fn status_line(app: &App) -> serde_json::Result<String> {
+    serde_json::to_string(&Status::new("status", app))
+}
+
+/// Sets a flag once the process receives Ctrl-C, so the loop can exit cleanly
+fn interrupt_flag() -> Result<Arc<AtomicBool>> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(SIGINT, Arc::clone(&interrupted))?;
+    Ok(interrupted)
+}
+
+/// Runs the timer without a TUI, rewriting the remaining time in place on a single line of
+/// stdout until the process is interrupted
+pub fn run_inline(app: &mut App, channels: &mut SideChannels, tick_rate: u64) -> Result<()> {
+    app.chime_if_running();
+
+    let interrupted = interrupt_flag()?;
+    let pause_requested = event::pause_flag()?;
+
+    let mut stdout = io::stdout();
+    let mut announced = false;
+
+    while app.running && !interrupted.load(Ordering::Relaxed) {
+        if pause_requested.swap(false, Ordering::Relaxed) {
+            toggle_pause(app);
+        }
+        app.tick();
+        channels.update(app);
+
+        if app.state == State::Triggered && !announced {
+            writeln!(stdout)?;
+            if let Some(label) = app.label() {
+                writeln!(stdout, "{label}")?;
+            }
+            if let Some(error) = &app.error {
+                eprintln!("{error}");
+            }
+            announced = true;
+        }
+
+        write!(stdout, "\r{}", app.clock_string().trim_start())?;
+        stdout.flush()?;
+
+        thread::sleep(Duration::from_millis(tick_rate));
+    }
+
+    writeln!(stdout)?;
+    Ok(())
+}
+
+/// Runs the timer without a TUI, printing a JSON status line on stdout every second and a final
+/// `triggered` event when the timer goes off. The alarm keeps playing until interrupted
+pub fn run_json(app: &mut App, channels: &mut SideChannels, tick_rate: u64) -> Result<()> {
+    app.chime_if_running();
+
+    let interrupted = interrupt_flag()?;
+    let pause_requested = event::pause_flag()?;
+
+    let mut stdout = io::stdout();
+    let mut last_second = None;
+    let mut last_state = None;
+    let mut announced = false;
+
+    while app.running && !interrupted.load(Ordering::Relaxed) {
+        if pause_requested.swap(false, Ordering::Relaxed) {
+            toggle_pause(app);
+        }
+        app.tick();
+        channels.update(app);
+
+        if app.state == State::Triggered {
+            if !announced {
+                serde_json::to_writer(&mut stdout, &Status::new("triggered", app))?;
+                writeln!(stdout)?;
+                announced = true;
+            }
+        } else if last_second != Some(app.time_left.num_seconds()) || last_state != Some(app.state) {
+            serde_json::to_writer(&mut stdout, &Status::new("status", app))?;
+            writeln!(stdout)?;
+            last_second = Some(app.time_left.num_seconds());
+            last_state = Some(app.state);
+        }
+
+        stdout.flush()?;
+
+        thread::sleep(Duration::from_millis(tick_rate));
+    }
+
+    Ok(())
+}
+
+/// Describes a duration the way it would be read out, like "1 hour 30 minutes"
+#[allow(clippy::modulo_arithmetic)]
+fn spoken_duration(duration: chrono::Duration) -> String {
+    let parts = [
+        (duration.num_hours(), "hour"),
+        (duration.num_minutes() % 60, "minute"),
+        (duration.num_seconds() % 60, "second"),
+    ];
+
+    let words: Vec<String> = parts
+        .into_iter()
+        .filter(|&(amount, _)| amount != 0)
+        .map(|(amount, unit)| {
+            let plural = if amount.abs() == 1 { "" } else { "s" };
+            format!("{} {unit}{plural}", amount.abs())
+        })
+        .collect();
+
+    if words.is_empty() {
+        String::from("0 seconds")
+    } else {
+        words.join(" ")
+    }
+}
+
+/// Whole minutes left, rounded up so the count drops exactly as each minute passes
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+fn minutes_left(app: &App) -> i64 {
+    (app.time_left.num_seconds().max(0) + 59) / 60
+}
+
+/// What to announce after the timer moves from `previous` to its current state, if anything
+fn announcement(previous: State, previous_minutes: i64, app: &App) -> Option<String> {
+    let remaining = || spoken_duration(app.time_left);
+
+    match (previous, app.state) {
+        (State::Paused, State::Running) => Some(format!("Resumed, {} remaining", remaining())),
+        (State::Quitting, State::Running | State::Paused) => Some(String::from("Carrying on")),
+        (State::Running | State::Restart, State::Running)
+            if minutes_left(app) != previous_minutes && app.time_left < app.duration =>
+        {
+            let minutes = chrono::Duration::minutes(minutes_left(app));
+            Some(format!("{} remaining", spoken_duration(minutes)))
+        }
+        (_, State::Running) if previous != State::Running && app.time_left >= app.duration => {
+            Some(match app.label() {
+                Some(label) => format!("Timer started, {}, {label}", spoken_duration(app.duration)),
+                None => format!("Timer started, {}", spoken_duration(app.duration)),
+            })
+        }
+        (State::Running, State::Paused) => Some(format!("Paused, {} remaining", remaining())),
+        (State::Running, State::Restart) => {
+            Some(String::from("Press restart again to restart the timer"))
+        }
+        (State::Standby, State::Standby) if minutes_left(app) != previous_minutes => {
+            Some(format!("{} selected", spoken_duration(app.duration)))
+        }
+        (_, State::Standby) if previous != State::Standby => Some(format!(
+            "Standby, {} selected. Use the arrow keys to pick another and Enter to start",
+            spoken_duration(app.duration)
+        )),
+        (_, State::Quitting) if previous != State::Quitting => {
+            Some(String::from("Quit? Press y to confirm or n to carry on"))
+        }
+        (_, State::Triggered) if previous != State::Triggered => Some(match app.label() {
+            Some(label) => format!("Timer finished, {label}"),
+            None => String::from("Timer finished"),
+        }),
+        _ => None,
+    }
+}
+
+/// Runs the timer without a TUI, printing a plain line of text whenever something a screen
+/// reader user would want to hear about happens. Keys work the same as in the TUI
+pub fn run_accessible(app: &mut App, channels: &mut SideChannels, tick_rate: u64) -> Result<()> {
+    app.chime_if_running();
+
+    let mut stdout = io::stdout();
+    let events = Handler::new(tick_rate);
+
+    terminal::enable_raw_mode()?;
+
+    if app.state == State::Waiting {
+        if let Some(start_time) = app.start_time {
+            write!(stdout, "Timer waiting, starts at {}\r\n", start_time.format("%H:%M"))?;
+        }
+    }
+
+    let mut previous = State::Waiting;
+    let mut previous_minutes = minutes_left(app);
+    let mut announce = |app: &mut App| -> Result<()> {
+        if let Some(line) = announcement(previous, previous_minutes, app) {
+            write!(stdout, "{line}\r\n")?;
+            if let Some(error) = &app.error {
+                write!(stdout, "{error}\r\n")?;
+            }
+            stdout.flush()?;
+        }
+
+        previous = app.state;
+        previous_minutes = minutes_left(app);
+        Ok(())
+    };
+
+    announce(app)?;
+    run_loop(app, events.iter(), |app, event| {
+        match event {
+            Event::Suspend => {
+                terminal::disable_raw_mode()?;
+                event::suspend_process()?;
+                terminal::enable_raw_mode()?;
+            }
+            Event::Tick => channels.update(app),
+            Event::Key(_) | Event::Mouse(_) | Event::Resize(_, _) | Event::TogglePause => {}
+        }
+
+        announce(app)
+    })?;
+
+    terminal::disable_raw_mode()?;
+    Ok(())
+}
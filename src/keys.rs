@@ -0,0 +1,124 @@
+use crate::app::Result;
+use serde::Deserialize;
+
+/// Keys the handler acts on before looking at the keymap, so binding one would never fire: `y`
+/// and `n` answer the quit prompt, `1`-`9` pick a preset and `t` skips to the end under
+/// `--debug-keys`
+pub const RESERVED_KEYS: [char; 12] = ['y', 'n', 't', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    Pause,
+    Restart,
+    Quit,
+    AddTime,
+    SubtractTime,
+    Lap,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    Extend,
+    Recolour,
+    Info,
+    Edit,
+    Elapsed,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct KeyMap {
+    pub pause: char,
+    pub restart: char,
+    pub quit: char,
+    pub add_time: char,
+    pub subtract_time: char,
+    pub lap: char,
+    pub volume_up: char,
+    pub volume_down: char,
+    pub mute: char,
+    pub extend: char,
+    pub recolour: char,
+    pub info: char,
+    pub edit: char,
+    pub elapsed: char,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            pause: ' ',
+            restart: 'r',
+            quit: 'q',
+            add_time: '+',
+            subtract_time: '-',
+            lap: 'l',
+            volume_up: ']',
+            volume_down: '[',
+            mute: 'm',
+            extend: 'e',
+            recolour: 'c',
+            info: 'i',
+            edit: ':',
+            elapsed: 'd',
+        }
+    }
+}
+
+impl KeyMap {
+    fn bindings(&self) -> [(Action, char); 14] {
+        [
+            (Action::Pause, self.pause),
+            (Action::Restart, self.restart),
+            (Action::Quit, self.quit),
+            (Action::AddTime, self.add_time),
+            (Action::SubtractTime, self.subtract_time),
+            (Action::Lap, self.lap),
+            (Action::VolumeUp, self.volume_up),
+            (Action::VolumeDown, self.volume_down),
+            (Action::Mute, self.mute),
+            (Action::Extend, self.extend),
+            (Action::Recolour, self.recolour),
+            (Action::Info, self.info),
+            (Action::Edit, self.edit),
+            (Action::Elapsed, self.elapsed),
+        ]
+    }
+
+    /// The key bound to an action
+    #[must_use]
+    pub fn key(&self, action: Action) -> char {
+        self.bindings()
+            .into_iter()
+            .find(|&(bound, _)| bound == action)
+            .map_or(' ', |(_, key)| key)
+    }
+
+    /// Finds the action bound to a key, ignoring case for letters
+    #[must_use]
+    pub fn action(&self, key: char) -> Option<Action> {
+        self.bindings()
+            .into_iter()
+            .find(|&(_, bound)| bound.eq_ignore_ascii_case(&key))
+            .map(|(action, _)| action)
+    }
+
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn validate(&self) -> Result<()> {
+        let bindings = self.bindings();
+
+        for (i, &(action, key)) in bindings.iter().enumerate() {
+            if RESERVED_KEYS.iter().any(|reserved| reserved.eq_ignore_ascii_case(&key)) {
+                return Err(format!("key '{key}' for {action:?} is reserved and can't be rebound").into());
+            }
+            if let Some(&(other, _)) = bindings
+                .iter()
+                .skip(i + 1)
+                .find(|&&(_, bound)| bound.eq_ignore_ascii_case(&key))
+            {
+                return Err(format!("key '{key}' is bound to both {action:?} and {other:?}").into());
+            }
+        }
+
+        Ok(())
+    }
+}
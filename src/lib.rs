@@ -5,3 +5,31 @@ pub mod event;
 pub mod tui;
 
 pub mod handler;
+
+pub mod config;
+
+pub mod keys;
+
+pub mod headless;
+
+pub mod digits;
+
+pub mod socket;
+
+pub mod session;
+
+pub mod schedule;
+
+pub mod awake;
+
+pub mod logging;
+
+pub mod fifo;
+
+pub mod tmux;
+
+pub mod pidfile;
+
+pub mod speech;
+
+pub mod channels;
@@ -0,0 +1,97 @@
+use crate::app::Result;
+use chrono::Local;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Appends log records to a file, since anything written to stderr would land on top of the TUI
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if let Ok(mut file) = self.file.lock() {
+            let _result = writeln!(
+                file,
+                "{} {:<5} {}: {}",
+                Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _result = file.flush();
+        }
+    }
+}
+
+#[must_use]
+pub fn default_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("cli-timer").join("cli-timer.log"))
+}
+
+/// How big the log may grow before it is moved aside to `cli-timer.log.old` and started afresh
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Sends log records at `level` and above to the given file, or to one in the user cache
+/// directory. Does nothing for `off`. The log is only there to help diagnose problems, so if it
+/// can't be opened this warns once on stderr and the timer runs without one
+pub fn init(path: Option<&Path>, level: LevelFilter) {
+    if level == LevelFilter::Off {
+        return;
+    }
+
+    if let Err(e) = try_init(path, level) {
+        eprintln!("cli-timer: running without a log, {e}");
+    }
+}
+
+fn try_init(path: Option<&Path>, level: LevelFilter) -> Result<()> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => default_path().ok_or("could not find a cache directory for the log")?,
+    };
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("could not create log directory {}: {e}", dir.display()))?;
+    }
+    rotate(&path);
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("could not open log file {}: {e}", path.display()))?;
+
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }))?;
+    log::set_max_level(level);
+
+    Ok(())
+}
+
+/// Moves the log aside once it has grown past [`MAX_LOG_BYTES`], replacing the last one moved
+/// aside, so that it never takes more than twice that on disk
+fn rotate(path: &Path) {
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() > MAX_LOG_BYTES) {
+        let _result = fs::rename(path, path.with_extension("log.old"));
+    }
+}
@@ -1,33 +1,213 @@
 use clap::Parser;
-use cli_timer::app::{App, Args, Result};
-use cli_timer::event::{Event, Handler};
-use cli_timer::handler::handle_key_events;
+use cli_timer::app::{App, Cli, ExecOn, Result, State};
+use cli_timer::awake::KeepAwake;
+use cli_timer::channels::SideChannels;
+use cli_timer::config::Config;
+use cli_timer::digits;
+use cli_timer::event::{Event, Handler, TICK_RATE};
+use cli_timer::handler::run_loop;
+use cli_timer::headless;
+use cli_timer::logging;
+use cli_timer::pidfile::PidFile;
+use cli_timer::session;
+use cli_timer::speech;
+use cli_timer::tmux;
 use cli_timer::tui::Tui;
-use std::io;
+use std::io::{self, IsTerminal};
+use std::process::{Command, ExitCode};
+use std::thread;
+use std::time::{Duration, Instant};
 use tui::backend::CrosstermBackend;
 use tui::Terminal;
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+fn main() -> ExitCode {
+    // Everything that can go wrong with the arguments is caught before the terminal is taken
+    // over, so the message stays readable on the normal screen
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("{e}");
+            eprintln!("cli-timer: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let args = Cli::parse().into_args()?;
+    logging::init(args.common.log_file.as_deref(), args.common.log_level);
+
+    if let Some(amount) = args.countdown.add {
+        return session::extend(args.common.session.flatten().as_deref(), amount);
+    }
+
+    if args.common.list_fonts {
+        return digits::print_fonts(&mut io::stdout().lock());
+    }
+
+    if args.common.detach {
+        if tmux::inside() {
+            return tmux::detach(args.common.label.first().map(String::as_str));
+        }
+        log::warn!("--detach only works inside tmux, running the timer here");
+        eprintln!("--detach only works inside tmux, running the timer here");
+    }
+
+    if args.common.speak && speech::engine().is_none() {
+        log::warn!("--speak found no text-to-speech program, so the label won't be read out");
+        eprintln!("--speak found no text-to-speech program, so the label won't be read out");
+    }
+
+    // The TUI is drawn on stderr, so when that has been redirected fall back to printing the
+    // time on stdout rather than filling the redirect target with escape codes
+    let tui_unavailable = !io::stderr().is_terminal();
+    let drawing_tui = !(args.common.inline || args.common.json || args.common.accessible);
+    if tui_unavailable && drawing_tui && !io::stdout().is_terminal() {
+        return Err("neither stdout nor stderr is a terminal, use --json to run without one".into());
+    }
+
+    let inline = args.common.inline || (tui_unavailable && drawing_tui);
+    let json = args.common.json;
+    let accessible = args.common.accessible;
+    let alternate_screen = !args.common.no_alternate_screen;
+    let remember = args.common.remember;
+    let summary = !args.common.no_summary;
+    let config_path = args.common.config.clone();
+    let socket_path = args.common.socket.clone();
+    let fifo_path = args.common.fifo.clone();
+    let session_path = args.common.session.clone();
+    let save_config = args.common.save_config.clone();
+    let exec = args.common.exec.clone();
+    let exec_on = args.common.exec_on;
+
+    let mut config = Config::load(config_path.as_deref())?;
+    if let Some(profile) = &args.common.profile {
+        config.use_profile(profile)?;
+    }
+
+    let keep_awake = args.common.keep_awake;
+    let pid_path = args.common.pid_file.clone();
+    let grace = args.common.grace.map(Duration::from_millis);
+    let frame_budget = args.common.max_fps.map(|fps| Duration::from_secs(1) / fps);
+    let mut app = App::new(args, config)?;
+
+    let _awake = keep_awake.then(KeepAwake::start).transpose()?;
+    let _pid_file = pid_path.as_deref().map(PidFile::create).transpose()?;
+
+    if (inline || json) && app.state == State::Standby {
+        return Err("--preset needs a key to pick one, so it can't run with --inline or --json".into());
+    }
+
+    if let Some(path) = save_config {
+        Config::save(&path, &app.settings(), &app.preferences())?;
+    }
 
-    let mut app = App::new(args);
+    let mut channels = SideChannels::open(
+        session_path.as_ref().map(Option::as_deref),
+        socket_path.as_deref(),
+        fifo_path.as_deref(),
+    )?;
+
+    if inline {
+        headless::run_inline(&mut app, &mut channels, TICK_RATE)?;
+        return run_exec(exec.as_deref(), exec_on, &app);
+    }
+
+    if json {
+        headless::run_json(&mut app, &mut channels, TICK_RATE)?;
+        return run_exec(exec.as_deref(), exec_on, &app);
+    }
+
+    if accessible {
+        headless::run_accessible(&mut app, &mut channels, TICK_RATE)?;
+        return run_exec(exec.as_deref(), exec_on, &app);
+    }
 
     let backend = CrosstermBackend::new(io::stderr());
-    let terminal = Terminal::new(backend)?;
-    let events = Handler::new(250);
-    let mut tui = Tui::new(terminal, events);
+    let events = Handler::new(TICK_RATE);
+    let mut tui = if alternate_screen {
+        Tui::new(Terminal::new(backend)?)
+    } else {
+        Tui::in_scrollback(backend)?
+    };
     tui.init()?;
+    // Draw before the first tick so the full duration is on screen from the start
+    tui.draw(&mut app)?;
+    if let Some(grace) = grace {
+        thread::sleep(grace);
+        app.start_from_now();
+    }
+    app.chime_if_running();
 
-    while app.running {
-        tui.draw(&mut app)?;
+    // What was on the clock at the last draw, so that under --max-fps only ticks that change
+    // something on screen are drawn, and no sooner than the budget allows
+    let mut last_draw = (Instant::now(), app.state, app.clock_string());
+    // Whether a change is waiting on the budget to be drawn
+    let mut pending = false;
 
-        match tui.events.next()? {
-            Event::Tick => app.tick(),
-            Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
-            Event::Mouse(_) | Event::Resize(_, _) => {}
+    // Errors here drop `tui` on the way out, which hands the terminal back before main prints them
+    run_loop(&mut app, events.iter(), |app, event| {
+        match event {
+            Event::Suspend => return tui.suspend(app),
+            Event::Tick => channels.update(app),
+            Event::Key(_) | Event::Mouse(_) | Event::Resize(_, _) | Event::TogglePause => {}
         }
-    }
+
+        // Without a budget every event redraws
+        let (drawn_at, state, clock) = &last_draw;
+        pending |= frame_budget.is_none()
+            || !matches!(event, Event::Tick)
+            || *state != app.state
+            || *clock != app.clock_string()
+            || app.animating();
+        if pending && frame_budget.is_none_or(|budget| drawn_at.elapsed() >= budget) {
+            pending = false;
+            last_draw = (Instant::now(), app.state, app.clock_string());
+            tui.draw(app)?;
+        }
+        Ok(())
+    })?;
 
     tui.exit()?;
+    drop(channels);
+
+    if summary {
+        if let Some(line) = app.summary() {
+            println!("{line}");
+        }
+        if let Some(line) = app.seed_line() {
+            eprintln!("{line}");
+        }
+    }
+
+    if remember {
+        Config::save_preferences(config_path.as_deref(), &app.preferences())?;
+    }
+
+    run_exec(exec.as_deref(), exec_on, &app)
+}
+
+/// Runs the `--exec` command through the shell and waits for it, unless it should only follow
+/// a timer that went off and this one was quit first
+fn run_exec(command: Option<&str>, on: ExecOn, app: &App) -> Result<()> {
+    let Some(command) = command else {
+        return Ok(());
+    };
+    if on == ExecOn::Success && !app.completed {
+        log::info!("not running --exec as the timer was quit before it went off");
+        return Ok(());
+    }
+
+    log::info!("running {command}");
+    let status = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).status()
+    } else {
+        Command::new("sh").args(["-c", command]).status()
+    }
+    .map_err(|e| format!("could not run {command}: {e}"))?;
+
+    if !status.success() {
+        log::warn!("{command} exited with {status}");
+    }
     Ok(())
 }
@@ -0,0 +1,61 @@
+use crate::app::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Holds the process ID in a file for as long as the timer runs, so scripts can find it to
+/// send SIGUSR1. The file is removed on exit
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the PID to `path`. A file left behind by a timer that is no longer running is
+    /// replaced, but one belonging to a live process is left alone
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Ok(contents) = fs::read_to_string(path) {
+            match contents.trim().parse::<u32>() {
+                Ok(pid) if alive(pid) => {
+                    return Err(format!(
+                        "{} belongs to process {pid}, which is still running",
+                        path.display()
+                    )
+                    .into());
+                }
+                _ => log::warn!("replacing stale PID file {}", path.display()),
+            }
+        }
+
+        fs::write(path, format!("{}\n", process::id()))
+            .map_err(|e| format!("could not write {}: {e}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _result = fs::remove_file(&self.path);
+    }
+}
+
+/// Whether a process with this ID exists. Without a signal to probe it with, a PID is taken to
+/// be stale
+#[cfg(unix)]
+fn alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists and can be signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn alive(_pid: u32) -> bool {
+    false
+}
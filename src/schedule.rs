@@ -0,0 +1,59 @@
+use crate::app::{expand_sound, parse_duration, Result, Timer};
+use std::{fs, path::Path};
+
+/// Reads a schedule of timers to run in sequence. Each line holds a duration, then optionally a
+/// label and a sound, separated by commas:
+///
+/// ```text
+/// # duration, label, sound
+/// 25m, Work, work.mp3
+/// 5m, Break
+/// ```
+///
+/// Blank lines and lines starting with `#` are skipped. Labels can't contain commas, but since
+/// the sound comes last its path can, and `~` and `$VAR` in it are expanded
+pub fn load(path: &Path) -> Result<Vec<Timer>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("could not read schedule {}: {e}", path.display()))?;
+
+    let mut timers = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let timer = parse_line(line).map_err(|e| {
+            format!("{} line {}: {e}", path.display(), number.saturating_add(1))
+        })?;
+        timers.push(timer);
+    }
+
+    if timers.is_empty() {
+        return Err(format!("schedule {} has no timers in it", path.display()).into());
+    }
+
+    Ok(timers)
+}
+
+fn parse_line(line: &str) -> std::result::Result<Timer, String> {
+    let mut fields = line.splitn(3, ',').map(str::trim);
+    let non_empty = |field: Option<&str>| field.filter(|field| !field.is_empty()).map(String::from);
+
+    let duration = fields.next().unwrap_or_default();
+    let duration = parse_duration(duration).map_err(|e| format!("invalid duration {duration:?}: {e}"))?;
+
+    let label = non_empty(fields.next());
+    let sound = non_empty(fields.next())
+        .map(|sound| expand_sound(&sound))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    Ok(Timer {
+        duration,
+        label,
+        sound,
+        is_break: false,
+    })
+}
@@ -0,0 +1,140 @@
+use crate::app::{App, Result, State};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// What a running timer shares through its session file. Another process can change it, e.g.
+/// with `--add`, and the running timer adopts the change on its next tick
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+struct Session {
+    /// When the countdown ends, as RFC 3339, while it is counting
+    end_time: Option<String>,
+    /// Milliseconds left while the countdown is paused
+    paused_remaining: Option<i64>,
+}
+
+impl Session {
+    #[allow(clippy::arithmetic_side_effects)]
+    fn extend(&mut self, amount: Duration) -> Result<()> {
+        if let Some(remaining) = &mut self.paused_remaining {
+            *remaining += amount.num_milliseconds();
+        } else if let Some(end_time) = &self.end_time {
+            let end_time = DateTime::parse_from_rfc3339(end_time)? + amount;
+            self.end_time = Some(end_time.to_rfc3339());
+        }
+
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn default_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("cli-timer").join("session.toml"))
+}
+
+fn resolve(path: Option<&Path>) -> Result<PathBuf> {
+    match path {
+        Some(path) => Ok(path.to_path_buf()),
+        None => default_path().ok_or_else(|| "could not find a cache directory".into()),
+    }
+}
+
+/// Extends the timer sharing the given session file by `amount`
+pub fn extend(path: Option<&Path>, amount: Duration) -> Result<()> {
+    let path = resolve(path)?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("no running timer found at {}: {e}", path.display()))?;
+
+    let mut session: Session = toml::from_str(&contents)?;
+    session.extend(amount)?;
+    fs::write(&path, toml::to_string(&session)?)?;
+
+    Ok(())
+}
+
+/// Keeps a running timer and its session file in step
+pub struct SessionFile {
+    path: PathBuf,
+    last_written: Option<String>,
+}
+
+impl SessionFile {
+    pub fn create(path: Option<&Path>) -> Result<Self> {
+        let path = resolve(path)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        Ok(Self {
+            path,
+            last_written: None,
+        })
+    }
+
+    /// Applies changes other processes made to the file, then writes the timer's current state
+    pub fn sync(&mut self, app: &mut App) -> Result<()> {
+        if let Ok(contents) = fs::read_to_string(&self.path) {
+            if self.last_written.as_ref() != Some(&contents) {
+                if let Ok(session) = toml::from_str::<Session>(&contents) {
+                    apply(&session, app);
+                }
+            }
+        }
+
+        let contents = toml::to_string(&snapshot(app))?;
+        if self.last_written.as_ref() != Some(&contents) {
+            fs::write(&self.path, &contents)?;
+            self.last_written = Some(contents);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SessionFile {
+    fn drop(&mut self) {
+        let _result = fs::remove_file(&self.path);
+    }
+}
+
+fn snapshot(app: &App) -> Session {
+    match app.state {
+        State::Paused => Session {
+            end_time: None,
+            paused_remaining: Some(app.time_left.num_milliseconds()),
+        },
+        _ => Session {
+            end_time: Some(app.end_time.to_rfc3339()),
+            paused_remaining: None,
+        },
+    }
+}
+
+fn apply(session: &Session, app: &mut App) {
+    match (app.state, session) {
+        (
+            State::Paused,
+            Session {
+                paused_remaining: Some(remaining),
+                ..
+            },
+        ) => {
+            app.time_left = Duration::milliseconds(*remaining);
+        }
+        (
+            State::Running | State::Restart | State::Quitting,
+            Session {
+                end_time: Some(end_time),
+                ..
+            },
+        ) => {
+            if let Ok(end_time) = DateTime::parse_from_rfc3339(end_time) {
+                app.end_time = end_time.with_timezone(&Local);
+            }
+        }
+        _ => {}
+    }
+}
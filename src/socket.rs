@@ -0,0 +1,63 @@
+use crate::app::{App, Result};
+use crate::headless;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Serves the timer's latest status as a line of JSON to anyone connecting to a Unix socket
+pub struct StatusSocket {
+    path: PathBuf,
+    status: Arc<Mutex<String>>,
+}
+
+impl StatusSocket {
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> Result<Self> {
+        use std::io::Write;
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::{UnixListener, UnixStream};
+        use std::thread;
+
+        // A timer that was killed leaves its socket behind, which would make binding fail with
+        // "address in use". One no one answers on is stale, so it can go
+        let is_socket = path.metadata().is_ok_and(|metadata| metadata.file_type().is_socket());
+        if is_socket && UnixStream::connect(path).is_err() {
+            log::info!("removing the stale socket at {}", path.display());
+            std::fs::remove_file(path)
+                .map_err(|e| format!("could not remove stale socket {}: {e}", path.display()))?;
+        }
+
+        let listener = UnixListener::bind(path)
+            .map_err(|e| format!("could not listen on {}: {e}", path.display()))?;
+        let status = Arc::new(Mutex::new(String::new()));
+
+        let shared = Arc::clone(&status);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let line = shared.lock().unwrap_or_else(PoisonError::into_inner).clone();
+                let _result = writeln!(stream, "{line}");
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            status,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path) -> Result<Self> {
+        Err("--socket is only supported on Unix".into())
+    }
+
+    pub fn update(&self, app: &App) -> Result<()> {
+        let line = headless::status_line(app)?;
+        *self.status.lock().unwrap_or_else(PoisonError::into_inner) = line;
+        Ok(())
+    }
+}
+
+impl Drop for StatusSocket {
+    fn drop(&mut self) {
+        let _result = std::fs::remove_file(&self.path);
+    }
+}
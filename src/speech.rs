@@ -0,0 +1,42 @@
+use crate::app::Result;
+use std::env;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Text-to-speech programs to look for, most preferred first
+#[cfg(target_os = "macos")]
+const ENGINES: [&str; 1] = ["say"];
+#[cfg(not(target_os = "macos"))]
+const ENGINES: [&str; 3] = ["spd-say", "espeak-ng", "espeak"];
+
+/// The first text-to-speech program found on the `PATH`
+#[must_use]
+pub fn engine() -> Option<&'static str> {
+    let path = env::var_os("PATH")?;
+    ENGINES
+        .into_iter()
+        .find(|engine| env::split_paths(&path).any(|dir| dir.join(engine).is_file()))
+}
+
+/// Starts reading the text aloud and returns without waiting for it to finish
+pub fn speak(text: &str) -> Result<()> {
+    let engine = engine().ok_or_else(|| {
+        format!("no text-to-speech program found, install one of {}", ENGINES.join(", "))
+    })?;
+
+    // After `--` so a label like "-5 min break" isn't taken for an option
+    let mut child = Command::new(engine)
+        .args(["--", text])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not speak with {engine}: {e}"))?;
+
+    // Reaped in the background so it doesn't linger once it has finished speaking
+    thread::spawn(move || {
+        let _result = child.wait();
+    });
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::app::Result;
+use std::env;
+use std::process::Command;
+
+/// Whether this process is running inside a tmux session
+#[must_use]
+pub fn inside() -> bool {
+    env::var_os("TMUX").is_some_and(|value| !value.is_empty())
+}
+
+/// Starts this timer over again in a new tmux window in the background, named after its
+/// label, with the same arguments apart from `--detach`. The window starts in the current
+/// directory so relative paths in the arguments still point at the same files
+pub fn detach(label: Option<&str>) -> Result<()> {
+    let program = env::current_exe()?;
+    let directory = env::current_dir()?;
+    let args = env::args_os().skip(1).filter(|arg| arg != "--detach");
+
+    let status = Command::new("tmux")
+        .args(["new-window", "-d", "-n", label.unwrap_or("timer"), "-c"])
+        .arg(directory)
+        .arg("--")
+        .arg(program)
+        .args(args)
+        .status()
+        .map_err(|e| format!("could not run tmux: {e}"))?;
+
+    if !status.success() {
+        return Err(format!("tmux could not open a window for the timer ({status})").into());
+    }
+
+    Ok(())
+}
@@ -1,27 +1,63 @@
 use crate::app::{App, Result};
-use crate::event::Handler;
+use crate::event;
+use crossterm::cursor;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
-use std::io;
+use std::io::{self, Write};
 use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::terminal::{TerminalOptions, Viewport};
 use tui::Terminal;
 
-#[allow(clippy::partial_pub_fields)]
+/// Number of terminal rows the timer occupies when drawn in the scrollback
+pub const SCROLLBACK_HEIGHT: u16 = 3;
+
+/// Owns the terminal while the timer is drawn, handing it back on drop so that an error or panic
+/// part way through never leaves the shell in raw mode on the alternate screen
 pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
-    pub events: Handler,
+    scrollback_area: Option<Rect>,
+    /// Whether the terminal is currently set up for drawing, so it is only handed back once
+    active: bool,
 }
 
 impl<B: Backend> Tui<B> {
-    pub fn new(terminal: Terminal<B>, events: Handler) -> Self {
-        Self { terminal, events }
+    pub const fn new(terminal: Terminal<B>) -> Self {
+        Self {
+            terminal,
+            scrollback_area: None,
+            active: false,
+        }
+    }
+
+    /// Draws below the cursor instead of taking over the screen, so the output stays in the
+    /// scrollback once the timer exits
+    pub fn in_scrollback(backend: B) -> Result<Self> {
+        let area = scrollback_viewport()?;
+        let terminal = Terminal::with_options(
+            backend,
+            TerminalOptions {
+                viewport: Viewport::fixed(area),
+            },
+        )?;
+
+        Ok(Self {
+            terminal,
+            scrollback_area: Some(area),
+            active: false,
+        })
     }
 
     pub fn init(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        self.active = true;
+
+        if self.scrollback_area.is_none() {
+            crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+            self.terminal.clear()?;
+        }
+
         self.terminal.hide_cursor()?;
-        self.terminal.clear()?;
         Ok(())
     }
 
@@ -30,10 +66,63 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Hands the terminal back and stops the process, then sets the terminal up again and
+    /// redraws everything once it is continued
+    pub fn suspend(&mut self, app: &mut App) -> Result<()> {
+        self.exit()?;
+        event::suspend_process()?;
+        log::info!("resumed, setting the terminal up again");
+
+        self.init()?;
+        self.terminal.clear()?;
+        self.draw(app)
+    }
+
     pub fn exit(&mut self) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+        match self.scrollback_area {
+            Some(area) => {
+                self.terminal.set_cursor(0, area.bottom())?;
+            }
+            None => {
+                crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+            }
+        }
+
         self.terminal.show_cursor()?;
         Ok(())
     }
 }
+
+impl<B: Backend> Drop for Tui<B> {
+    fn drop(&mut self) {
+        if let Err(e) = self.exit() {
+            log::warn!("could not restore the terminal: {e}");
+        }
+    }
+}
+
+/// Makes room for the timer below the cursor and returns the area it should be drawn in
+fn scrollback_viewport() -> Result<Rect> {
+    let (width, _) = terminal::size()?;
+
+    let mut stderr = io::stderr();
+    for _ in 0..SCROLLBACK_HEIGHT {
+        writeln!(stderr)?;
+    }
+    stderr.flush()?;
+
+    let (_, row) = cursor::position()?;
+
+    Ok(Rect::new(
+        0,
+        row.saturating_sub(SCROLLBACK_HEIGHT),
+        width,
+        SCROLLBACK_HEIGHT,
+    ))
+}
@@ -0,0 +1,78 @@
+use chrono::Duration;
+use cli_timer::app::{App, State};
+use tui::style::Color;
+
+#[test]
+fn builds_a_running_timer() {
+    let app = App::builder()
+        .duration(Duration::minutes(3))
+        .sound("alarm.wav")
+        .label("Tea")
+        .colour(Color::Green)
+        .volume(40)
+        .build()
+        .unwrap();
+
+    assert!(app.state == State::Running);
+    assert_eq!(app.time_left, Duration::minutes(3));
+    assert_eq!(app.label(), Some("Tea"));
+    assert_eq!(app.colour, Color::Green);
+    assert_eq!(app.volume, 40);
+}
+
+#[test]
+fn rejects_invalid_settings() {
+    assert!(App::builder().build().is_err());
+    assert!(App::builder().duration(Duration::minutes(1)).volume(101).build().is_err());
+    assert!(App::builder()
+        .duration(Duration::hours(2))
+        .max_duration(Duration::hours(1))
+        .build()
+        .is_err());
+}
+
+#[test]
+fn same_seed_picks_the_same_colours() {
+    let colours = |seed| {
+        let mut app = App::builder()
+            .duration(Duration::minutes(3))
+            .seed(seed)
+            .build()
+            .unwrap();
+
+        let mut colours = vec![app.colour];
+        for _ in 0..5 {
+            app.recolour();
+            colours.push(app.colour);
+        }
+        colours
+    };
+
+    assert_eq!(colours(42), colours(42));
+    assert_eq!(App::builder().duration(Duration::minutes(3)).seed(7).build().unwrap().seed, 7);
+}
+
+#[test]
+fn the_seed_is_only_printed_for_random_colours() {
+    let builder = || App::builder().duration(Duration::minutes(3)).seed(7);
+
+    let line = builder().build().unwrap().seed_line().unwrap();
+    assert!(line.contains("--seed 7"), "{line}");
+    assert!(builder().colour(Color::Red).build().unwrap().seed_line().is_none());
+    assert!(builder().plain(true).build().unwrap().seed_line().is_none());
+}
+
+#[test]
+fn animations_count_as_changes_on_screen() {
+    let builder = || App::builder().duration(Duration::minutes(3)).intro(false);
+
+    let mut app = builder().build().unwrap();
+    assert!(!app.animating());
+    app.end_time = chrono::Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert!(app.animating());
+
+    assert!(builder().marquee(true).build().unwrap().animating());
+    assert!(App::builder().duration(Duration::minutes(3)).build().unwrap().animating());
+}
@@ -0,0 +1,52 @@
+use chrono::Duration;
+use cli_timer::app::App;
+use cli_timer::channels::SideChannels;
+use cli_timer::socket::StatusSocket;
+use std::{env, fs, process};
+
+#[test]
+fn updates_the_session_file_without_a_tui() {
+    let path = env::temp_dir().join(format!("cli-timer-channels-session-{}.toml", process::id()));
+    let mut app = App::builder().duration(Duration::minutes(5)).build().unwrap();
+
+    let mut channels = SideChannels::open(Some(Some(&path)), None, None).unwrap();
+    channels.update(&mut app);
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("end_time"), "{contents}");
+
+    drop(channels);
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn replaces_a_socket_left_behind_by_a_killed_timer() {
+    use std::io::Read;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let path = env::temp_dir().join(format!("cli-timer-stale-socket-{}", process::id()));
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let app = App::builder().duration(Duration::minutes(5)).build().unwrap();
+    let socket = StatusSocket::bind(&path).unwrap();
+    socket.update(&app).unwrap();
+
+    let mut status = String::new();
+    UnixStream::connect(&path).unwrap().read_to_string(&mut status).unwrap();
+    assert!(status.contains("\"remaining_seconds\""), "{status}");
+
+    // One that a running timer still answers on is left alone
+    assert!(StatusSocket::bind(&path).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn leaves_a_file_that_isnt_a_socket_alone() {
+    let path = env::temp_dir().join(format!("cli-timer-not-a-socket-{}", process::id()));
+    fs::write(&path, "keep me").unwrap();
+
+    assert!(StatusSocket::bind(&path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,179 @@
+use chrono::Duration;
+use clap::Parser;
+use cli_timer::app::{expand_path, App, Args, Cli};
+use cli_timer::config::{Config, TimerMode};
+use cli_timer::keys::KeyMap;
+use cli_timer::schedule;
+use std::{env, fs, process};
+use tui::style::Color;
+
+#[test]
+fn saved_config_loads_back() {
+    let app = App::builder()
+        .duration(Duration::minutes(25))
+        .sound("alarm.wav")
+        .label("Focus")
+        .colour(Color::LightBlue)
+        .volume(40)
+        .build()
+        .unwrap();
+
+    let path = env::temp_dir().join(format!("cli-timer-save-config-{}.toml", process::id()));
+    Config::save(&path, &app.settings(), &app.preferences()).unwrap();
+    let config = Config::load(Some(&path));
+    fs::remove_file(&path).unwrap();
+
+    let config = config.unwrap();
+    assert_eq!(config.timer, app.settings());
+    assert_eq!(config.timer.mode, Some(TimerMode::Timer));
+    assert_eq!(config.timer.duration.as_deref(), Some("00:25:00"));
+    assert_eq!(config.timer.colour.as_deref(), Some("light-blue"));
+    assert_eq!(config.preferences.volume, Some(40));
+}
+
+#[test]
+fn refuses_to_save_what_a_config_cant_hold() {
+    let path = env::temp_dir().join(format!("cli-timer-unsavable-{}.toml", process::id()));
+    let path = path.to_string_lossy();
+    let runs: [&[&str]; 5] = [
+        &["-t", "25m", "-t", "5m"],
+        &["-t", "25m", "--sessions", "3"],
+        &["--until", "12:00"],
+        &["--preset", "5m"],
+        &["-t", "25m", "--repeat-whole-session"],
+    ];
+
+    for run in runs {
+        let mut argv = vec!["cli-timer", "-s", "alarm.wav", "--save-config", &path];
+        argv.extend(run);
+        let args = Args::try_parse_from(argv).unwrap();
+        let error = App::new(args, Config::default()).err().unwrap();
+        assert!(error.to_string().contains("single timer"), "{run:?}");
+    }
+
+    let cli = Cli::try_parse_from(["cli-timer", "pomodoro", "-s", "a.wav", "--save-config", &path]);
+    let error = App::new(cli.unwrap().into_args().unwrap(), Config::default()).err().unwrap();
+    assert!(error.to_string().contains("pomodoro"), "{error}");
+
+    let args = Args::try_parse_from(["cli-timer", "-t", "25m", "-s", "a.wav", "--save-config", &path]);
+    assert!(App::new(args.unwrap(), Config::default()).is_ok());
+}
+
+#[test]
+fn profile_overrides_timer_settings() {
+    let mut config: Config = toml::from_str(
+        r#"
+        [timer]
+        duration = "25m"
+        sound = "bell.wav"
+
+        [profile.cooking]
+        duration = "12m"
+        label = "Pasta"
+
+        [profile.work]
+        duration = "50m"
+        "#,
+    )
+    .unwrap();
+
+    config.use_profile("cooking").unwrap();
+    assert_eq!(config.timer.duration.as_deref(), Some("12m"));
+    assert_eq!(config.timer.label.as_deref(), Some("Pasta"));
+    assert_eq!(config.timer.sound.as_deref(), Some("bell.wav"));
+
+    let error = config.use_profile("gym").unwrap_err().to_string();
+    assert!(error.contains("available profiles: work"), "{error}");
+}
+
+#[test]
+fn palette_index_colours_are_saved_as_the_index() {
+    let parse = |colour: &str| {
+        Args::try_parse_from(["cli-timer", "-t", "5m", "-s", "alarm.wav", "--color", colour])
+    };
+
+    let app = App::new(parse("208").unwrap(), Config::default()).unwrap();
+    assert_eq!(app.colour, Color::Indexed(208));
+    assert_eq!(app.settings().colour.as_deref(), Some("208"));
+
+    assert!(parse("256").is_err());
+    assert!(parse("-1").is_err());
+}
+
+#[test]
+fn sound_paths_expand_variables_and_tilde() {
+    env::set_var("CLI_TIMER_TEST_SOUNDS", "/srv/sounds");
+
+    assert_eq!(expand_path("$CLI_TIMER_TEST_SOUNDS/alarm.wav").unwrap(), "/srv/sounds/alarm.wav");
+    assert_eq!(expand_path("${CLI_TIMER_TEST_SOUNDS}.wav").unwrap(), "/srv/sounds.wav");
+    if let Ok(home) = env::var("HOME") {
+        assert_eq!(expand_path("~/alarm.wav").unwrap(), format!("{home}/alarm.wav"));
+    }
+    assert_eq!(expand_path("a~b$/c.wav").unwrap(), "a~b$/c.wav");
+    assert!(expand_path("$CLI_TIMER_TEST_UNSET/alarm.wav").is_err());
+}
+
+#[test]
+fn expanded_sound_path_must_exist() {
+    env::set_var("CLI_TIMER_TEST_MISSING", "/nonexistent/cli-timer");
+    let args = Args::try_parse_from(["cli-timer", "-t", "5m", "-s", "$CLI_TIMER_TEST_MISSING/a.wav"])
+        .unwrap();
+
+    let error = App::new(args, Config::default()).err().unwrap();
+    assert!(error.to_string().contains("/nonexistent/cli-timer/a.wav"));
+}
+
+#[test]
+fn every_sound_flag_expands_its_path() {
+    env::set_var("CLI_TIMER_TEST_MISSING", "/nonexistent/cli-timer");
+    let flags: [&[&str]; 4] = [
+        &["--gentle-sound"],
+        &["--escalate", "--escalate-sound"],
+        &["--ticking", "--tick-sound"],
+        &["--sound-start"],
+    ];
+
+    for flag in flags {
+        let mut argv = vec!["cli-timer", "-t", "5m"];
+        argv.extend(flag);
+        argv.push("$CLI_TIMER_TEST_MISSING/b.wav");
+        let args = Args::try_parse_from(argv).unwrap();
+
+        let error = App::new(args, Config::default()).err().unwrap();
+        assert!(error.to_string().contains("/nonexistent/cli-timer/b.wav"), "{flag:?}");
+    }
+}
+
+#[test]
+fn schedule_sounds_expand_their_paths() {
+    let dir = env::temp_dir();
+    env::set_var("CLI_TIMER_TEST_SCHEDULE_SOUNDS", &dir);
+    let sound = dir.join(format!("cli-timer-schedule-sound-{}.wav", process::id()));
+    fs::write(&sound, b"").unwrap();
+    let file_name = sound.file_name().unwrap().to_string_lossy();
+
+    let path = env::temp_dir().join(format!("cli-timer-schedule-sounds-{}.txt", process::id()));
+    fs::write(&path, format!("5m, Work, $CLI_TIMER_TEST_SCHEDULE_SOUNDS/{file_name}\n")).unwrap();
+    let timers = schedule::load(&path).unwrap();
+    assert_eq!(timers[0].sound.as_deref(), Some(&*sound.to_string_lossy()));
+
+    fs::write(&path, "5m, Work\n5m, Rest, $CLI_TIMER_TEST_SCHEDULE_SOUNDS/missing.wav\n").unwrap();
+    let error = schedule::load(&path).err().unwrap().to_string();
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&sound).unwrap();
+    assert!(error.contains("line 2"), "{error}");
+    assert!(error.contains("missing.wav"), "{error}");
+}
+
+#[test]
+fn keys_the_handler_takes_first_cant_be_bound() {
+    for (binding, key) in [("mute", "y"), ("lap", "N"), ("info", "t"), ("extend", "5")] {
+        let keys: KeyMap = toml::from_str(&format!("{binding} = \"{key}\"")).unwrap();
+        let error = keys.validate().err().unwrap().to_string();
+        assert!(error.contains("reserved"), "{error}");
+    }
+
+    assert!(KeyMap::default().validate().is_ok());
+    let keys: KeyMap = toml::from_str("mute = \"x\"").unwrap();
+    assert!(keys.validate().is_ok());
+}
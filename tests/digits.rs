@@ -0,0 +1,35 @@
+use cli_timer::digits::print_fonts;
+use std::io::{self, ErrorKind, Write};
+
+/// Takes a few bytes then hangs up, like `head` closing its end of a pipe
+struct ClosingPipe(usize);
+
+impl Write for ClosingPipe {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0 == 0 {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+        let taken = buf.len().min(self.0);
+        self.0 -= taken;
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn lists_every_font_with_a_sample() {
+    let mut out = Vec::new();
+    print_fonts(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.lines().any(|line| line == "block"), "{out}");
+    assert!(out.lines().any(|line| line == "3x5"), "{out}");
+}
+
+#[test]
+fn a_reader_that_stops_early_isnt_an_error() {
+    assert!(print_fonts(&mut ClosingPipe(10)).is_ok());
+}
@@ -0,0 +1,264 @@
+use chrono::{Duration, Local};
+use clap::Parser;
+use cli_timer::app::{
+    format_time_left, human_time_left, parse_duration, App, Args, Cli, DurationFormat, State,
+};
+use cli_timer::config::Config;
+
+fn app_with(args: &[&str]) -> cli_timer::app::Result<App> {
+    let args = Args::try_parse_from(["cli-timer", "-s", "alarm.wav"].iter().chain(args))?;
+    App::new(args, Config::default())
+}
+
+#[test]
+fn parses_hours_minutes_seconds() {
+    assert_eq!(parse_duration("01:02:03"), Ok(Duration::seconds(3723)));
+}
+
+#[test]
+fn rejects_durations_that_overflow() {
+    assert!(parse_duration("9223372036854775807:00:00").is_err());
+    assert!(parse_duration("9223372036854775:00:00").is_err());
+}
+
+#[test]
+fn accepts_duration_at_default_maximum() {
+    assert!(app_with(&["-t", "720:00:00"]).is_ok());
+}
+
+#[test]
+fn rejects_duration_past_default_maximum() {
+    assert!(app_with(&["-t", "720:00:01"]).is_err());
+}
+
+#[test]
+fn rejects_any_queued_duration_past_maximum() {
+    assert!(app_with(&["-t", "00:05:00", "-t", "999999:00:00"]).is_err());
+}
+
+#[test]
+fn maximum_is_configurable() {
+    assert!(app_with(&["-t", "01:00:01", "--max-duration", "01:00:00"]).is_err());
+    assert!(app_with(&["-t", "999:00:00", "--max-duration", "1000:00:00"]).is_ok());
+}
+
+#[test]
+fn parses_unit_durations() {
+    assert_eq!(parse_duration("90s"), Ok(Duration::seconds(90)));
+    assert_eq!(parse_duration("5m"), Ok(Duration::minutes(5)));
+    assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+    assert_eq!(parse_duration("+5m"), Ok(Duration::minutes(5)));
+    assert_eq!(parse_duration("-1m"), Ok(Duration::minutes(-1)));
+}
+
+#[test]
+fn rejects_malformed_unit_durations() {
+    assert!(parse_duration("5x").is_err());
+    assert!(parse_duration("m").is_err());
+    assert!(parse_duration("1h30").is_err());
+}
+
+#[test]
+fn runs_a_percentage_of_a_reference_duration() {
+    let app_of = |args: &[&str]| {
+        let cli = Cli::try_parse_from(["cli-timer", "-s", "alarm.wav"].iter().chain(args))?;
+        App::new(cli.into_args()?, Config::default())
+    };
+
+    let app = app_of(&["--of", "20m", "--percent", "75"]).unwrap();
+    assert_eq!(app.duration, Duration::minutes(15));
+
+    let app = app_of(&["--of", "90s", "--percent", "150"]).unwrap();
+    assert_eq!(app.duration, Duration::seconds(135));
+
+    assert!(app_of(&["--of", "20m"]).is_err());
+    assert!(app_of(&["--of", "20m", "--percent", "0"]).is_err());
+    assert!(app_of(&["--of", "1s", "--percent", "10"]).is_err());
+    assert!(app_of(&["--of", "20m", "--percent", "50", "-t", "5m"]).is_err());
+}
+
+#[test]
+fn each_mode_only_takes_the_flags_that_apply_to_it() {
+    let app_in = |args: &[&str]| {
+        let cli = Cli::try_parse_from(["cli-timer"].iter().chain(args))?;
+        App::new(cli.into_args()?, Config::default())
+    };
+
+    let app = app_in(&["stopwatch", "--countup-target", "10m", "-s", "alarm.wav"]).unwrap();
+    assert!(app.count_up);
+    assert_eq!(app.duration, Duration::minutes(10));
+    let app = app_in(&["pomodoro", "--work", "20m", "-s", "alarm.wav"]).unwrap();
+    assert_eq!(app.duration, Duration::minutes(20));
+    assert!(app_in(&["timer", "-t", "5m", "--sessions", "2", "-s", "alarm.wav"]).is_ok());
+
+    for countdown_only in [&["-t", "5m"][..], &["--of", "20m", "--percent", "50"], &["--until", "12:00"]] {
+        for mode in ["stopwatch", "pomodoro"] {
+            let args = [&[mode][..], countdown_only].concat();
+            let error = Cli::try_parse_from(["cli-timer"].iter().chain(&args)).err().unwrap();
+            assert_eq!(error.kind(), clap::error::ErrorKind::UnknownArgument, "{args:?}");
+        }
+    }
+    for flag in ["--preset", "--schedule", "--sessions"] {
+        assert!(Cli::try_parse_from(["cli-timer", "pomodoro", flag, "2"]).is_err(), "{flag}");
+    }
+}
+
+#[test]
+fn counts_up_to_the_duration_given() {
+    let app_counting = |args: &[&str]| {
+        let cli = Cli::try_parse_from(["cli-timer"].iter().chain(args))?;
+        App::new(cli.into_args()?, Config::default())
+    };
+
+    let app = app_counting(&["--count", "up", "-t", "10m", "-s", "alarm.wav"]).unwrap();
+    assert!(app.count_up);
+    assert_eq!(app.duration, Duration::minutes(10));
+
+    // Without a target it is a stopwatch, so no sound is needed
+    let app = app_counting(&["--count", "up"]).unwrap();
+    assert!(app.count_up);
+
+    let app = app_counting(&["--count", "down", "-t", "10m", "-s", "alarm.wav"]).unwrap();
+    assert!(!app.count_up);
+
+    assert!(app_counting(&["--count", "up", "-t", "1m", "-t", "2m", "-s", "alarm.wav"]).is_err());
+}
+
+#[test]
+fn clock_keeps_its_width_for_timers_over_100_hours() {
+    let mut app = app_with(&["-t", "100:00:00"]).unwrap();
+    assert_eq!(app.clock_string(), " 100:00:00");
+
+    app.time_left = Duration::hours(5);
+    assert_eq!(app.clock_string(), " 005:00:00");
+
+    app.time_left = Duration::seconds(59);
+    assert_eq!(app.clock_string(), " 000:00:59");
+}
+
+#[test]
+fn frozen_clock_stays_at_zero_once_triggered() {
+    let mut app = app_with(&["-t", "00:00:10", "--freeze-on-trigger"]).unwrap();
+
+    app.end_time = Local::now() - Duration::seconds(5);
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert_eq!(app.clock_string(), " 00:00:00");
+
+    app.end_time = Local::now() - Duration::seconds(65);
+    app.tick();
+    assert_eq!(app.clock_string(), " 00:00:00");
+}
+
+#[test]
+fn formats_time_left_without_a_terminal() {
+    let hms = DurationFormat::Hms;
+
+    assert_eq!(format_time_left(Duration::minutes(90), false, 0, &hms), " 01:30:00");
+    assert_eq!(format_time_left(Duration::seconds(-5), true, 0, &hms), "+00:00:05");
+    assert_eq!(format_time_left(Duration::zero(), true, 2, &hms), "+00:00:00");
+    assert_eq!(format_time_left(Duration::hours(5), false, 3, &hms), " 005:00:00");
+}
+
+#[test]
+fn formats_time_left_in_each_duration_format() {
+    let format = |seconds, format: &str| {
+        let format = DurationFormat::parse(format).unwrap();
+        format_time_left(Duration::seconds(seconds), false, 0, &format)
+    };
+
+    assert_eq!(format(5400, "ms"), " 90:00");
+    assert_eq!(format(65, "ms"), " 01:05");
+    assert_eq!(format(5400, "compact"), " 1:30:00");
+    assert_eq!(format(300, "compact"), " 5:00");
+    assert_eq!(format(45, "compact"), " 45");
+    assert_eq!(format(3725, "%h h %M m"), " 1 h 02 m");
+    assert_eq!(format(90, "%ss (100%%)"), " 90s (100%)");
+
+    assert!(DurationFormat::parse("%M:%Q").is_err());
+    assert!(DurationFormat::parse("%M:%").is_err());
+    assert!(DurationFormat::parse("minutes").is_err());
+}
+
+#[test]
+fn describes_time_left_in_rough_buckets() {
+    let left = |seconds| human_time_left(Duration::seconds(seconds), false);
+
+    assert_eq!(left(0), "less than a minute left");
+    assert_eq!(left(59), "less than a minute left");
+    assert_eq!(left(60), "about a minute left");
+    assert_eq!(left(89), "about a minute left");
+    assert_eq!(left(90), "about 2 minutes left");
+    assert_eq!(left(59 * 60 + 29), "about 59 minutes left");
+    assert_eq!(left(59 * 60 + 30), "about an hour left");
+    assert_eq!(left(90 * 60 - 1), "about an hour left");
+    assert_eq!(left(90 * 60), "about 2 hours left");
+    assert_eq!(human_time_left(Duration::seconds(-5), true), "overtime");
+}
+
+#[test]
+fn alarm_duration_is_kept_apart_from_auto_exit() -> cli_timer::app::Result<()> {
+    let app = app_with(&["-t", "1m", "--auto-exit", "--alarm-duration"])?;
+    assert_eq!(app.auto_exit, Some(Duration::seconds(10)));
+    assert_eq!(app.alarm_duration, Some(Duration::seconds(2)));
+
+    let app = app_with(&["-t", "1m", "--auto-exit", "30s", "--alarm-duration", "5s"])?;
+    assert_eq!(app.auto_exit, Some(Duration::seconds(30)));
+    assert_eq!(app.alarm_duration, Some(Duration::seconds(5)));
+
+    assert!(Args::try_parse_from(["cli-timer", "-t", "1m", "--alarm-duration", "5s"]).is_err());
+    Ok(())
+}
+
+#[test]
+fn bar_fill_shrinks_with_the_time_left_and_flashes_when_done() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .bar_fill(true)
+        .build()
+        .unwrap();
+
+    app.time_left = Duration::seconds(15);
+    assert_eq!(app.bar_fill_width(100), 25);
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    let widths: Vec<u16> = (0..2)
+        .map(|_| {
+            app.tick();
+            app.bar_fill_width(100)
+        })
+        .collect();
+    assert!(widths.contains(&0) && widths.contains(&100));
+}
+
+#[test]
+fn stages_follow_the_time_elapsed() -> cli_timer::app::Result<()> {
+    let mut app = app_with(&[
+        "-t",
+        "10m",
+        "--stage",
+        "5m:Main set: intervals",
+        "--stage",
+        "0:Warm up",
+    ])?;
+
+    app.time_left = Duration::minutes(8);
+    assert_eq!(app.current_stage(), Some("Warm up"));
+
+    app.time_left = Duration::minutes(5);
+    assert_eq!(app.current_stage(), Some("Main set: intervals"));
+
+    assert!(app_with(&["-t", "10m", "--stage", "10m:Cool down"]).is_err());
+    assert!(app_with(&["-t", "10m", "--stage", "Cool down"]).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_a_zero_or_negative_duration() {
+    for time in ["-t=0", "-t=00:00:00", "-t=0s", "-t=-5m", "-t=-90s"] {
+        let error = app_with(&[time]).err().unwrap().to_string();
+        assert!(error.contains("longer than zero"), "{error}");
+    }
+}
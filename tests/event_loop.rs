@@ -0,0 +1,440 @@
+use chrono::{Duration, Local};
+use cli_timer::app::{App, State, Timer};
+use cli_timer::event::Event;
+use cli_timer::handler::{handle_key_events, handles, key_hints, run_loop, HintKey};
+use cli_timer::keys::Action;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(c: char) -> Event {
+    code(KeyCode::Char(c))
+}
+
+fn code(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn app() -> App {
+    App::builder()
+        .duration(Duration::minutes(5))
+        .build()
+        .unwrap()
+}
+
+/// Runs the events through the loop, returning how many were handled
+fn run(app: &mut App, events: Vec<Event>) -> usize {
+    let mut handled = 0;
+    run_loop(app, events, |_, _| {
+        handled += 1;
+        Ok(())
+    })
+    .unwrap();
+    handled
+}
+
+#[test]
+fn pauses_and_resumes() {
+    let mut app = app();
+
+    run(&mut app, vec![Event::Tick, key(' '), Event::Tick]);
+    assert!(app.state == State::Paused);
+
+    run(&mut app, vec![key(' '), Event::Tick]);
+    assert!(app.state == State::Running);
+    assert!(app.running);
+}
+
+#[test]
+fn restart_needs_confirming() {
+    let mut app = app();
+
+    run(&mut app, vec![key('r')]);
+    assert!(app.state == State::Restart);
+
+    run(&mut app, vec![key('q')]);
+    assert!(app.state == State::Running);
+    assert!(app.running);
+
+    run(&mut app, vec![key('r'), key('r')]);
+    assert!(app.state == State::Running);
+    assert_eq!(app.time_left, Duration::minutes(5));
+}
+
+#[test]
+fn quitting_stops_the_loop() {
+    let mut app = app();
+
+    let handled = run(&mut app, vec![Event::Tick, key('q'), Event::Tick, Event::Tick]);
+    assert!(!app.running);
+    assert_eq!(handled, 2);
+}
+
+#[test]
+fn confirm_quit_asks_first() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(5))
+        .confirm_quit(true)
+        .build()
+        .unwrap();
+
+    run(&mut app, vec![key('q')]);
+    assert!(app.state == State::Quitting);
+    assert!(app.running);
+
+    run(&mut app, vec![key('n')]);
+    assert!(app.state == State::Running);
+
+    run(&mut app, vec![key('q'), key('y')]);
+    assert!(!app.running);
+}
+
+#[test]
+fn looping_session_sums_up_focus_and_break_time() {
+    let timer = |minutes, is_break| Timer {
+        duration: Duration::minutes(minutes),
+        label: None,
+        sound: None,
+        is_break,
+    };
+    let mut app = App::builder()
+        .timer(timer(25, false))
+        .timer(timer(5, true))
+        .repeat_whole_session(true)
+        .build()
+        .unwrap();
+
+    for _ in 0..3 {
+        app.end_time = Local::now() - Duration::seconds(1);
+        run(&mut app, vec![Event::Tick]);
+        assert!(app.state == State::Triggered);
+        run(&mut app, vec![key(' ')]);
+    }
+
+    assert_eq!(app.focus_sessions, 2);
+    assert_eq!(
+        app.summary().as_deref(),
+        Some("cli-timer: completed 2 focus sessions, 50 min focus and 5 min break in total")
+    );
+}
+
+#[test]
+fn pause_signal_toggles_only_a_counting_timer() {
+    let mut app = app();
+
+    run(&mut app, vec![Event::TogglePause]);
+    assert!(app.state == State::Paused);
+
+    run(&mut app, vec![Event::TogglePause]);
+    assert!(app.state == State::Running);
+
+    run(&mut app, vec![key('r'), Event::TogglePause]);
+    assert!(app.state == State::Restart);
+}
+
+#[test]
+fn standby_picks_a_preset_and_arms_it() {
+    let mut app = App::builder()
+        .presets(vec![Duration::minutes(5), Duration::minutes(25)])
+        .build()
+        .unwrap();
+    assert!(app.state == State::Standby);
+
+    run(&mut app, vec![Event::Tick, code(KeyCode::Down)]);
+    assert!(app.state == State::Standby);
+    assert_eq!(app.time_left, Duration::minutes(25));
+
+    run(&mut app, vec![code(KeyCode::Enter)]);
+    assert!(app.state == State::Running);
+
+    app.end_time = Local::now() - Duration::seconds(1);
+    run(&mut app, vec![Event::Tick, key(' ')]);
+    assert!(app.state == State::Standby);
+}
+
+#[test]
+fn intro_goes_away_after_a_couple_of_seconds() {
+    let mut app = app();
+    assert!(app.intro_ticks > 0);
+
+    run(&mut app, vec![Event::Tick; 8]);
+    assert_eq!(app.intro_ticks, 0);
+
+    let app = App::builder()
+        .duration(Duration::minutes(5))
+        .intro(false)
+        .build()
+        .unwrap();
+    assert_eq!(app.intro_ticks, 0);
+}
+
+#[test]
+fn sessions_run_the_timer_again_until_the_last_is_dismissed() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(5))
+        .sessions(2)
+        .build()
+        .unwrap();
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    app.dismiss();
+    assert!(app.running);
+    assert!(app.state == State::Running);
+    assert_eq!(app.session, 2);
+
+    app.end_time = Local::now();
+    app.tick();
+    app.dismiss();
+    assert!(!app.running);
+}
+
+#[test]
+fn hold_to_dismiss_keeps_one_press_of_any_key_from_silencing_the_alarm() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(5))
+        .hold_to_dismiss(true)
+        .build()
+        .unwrap();
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+
+    for c in [' ', 'r', 'm', 'e'] {
+        run(&mut app, vec![key(c)]);
+        assert!(app.state == State::Triggered, "{c:?}");
+        assert!(!app.muted, "{c:?}");
+    }
+    assert!(app.running);
+}
+
+#[test]
+fn auto_exit_runs_every_session_before_exiting() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(5))
+        .sessions(2)
+        .auto_exit(Duration::zero())
+        .build()
+        .unwrap();
+
+    app.end_time = Local::now();
+    app.tick();
+    app.tick();
+    assert!(app.running);
+    assert!(app.state == State::Running);
+    assert_eq!(app.session, 2);
+
+    app.end_time = Local::now();
+    app.tick();
+    app.tick();
+    assert!(!app.running);
+}
+
+#[test]
+fn auto_exit_starts_a_repeating_session_over() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(5))
+        .repeat_whole_session(true)
+        .auto_exit(Duration::zero())
+        .build()
+        .unwrap();
+
+    app.end_time = Local::now();
+    app.tick();
+    app.tick();
+    assert!(app.running);
+    assert!(app.state == State::Running);
+}
+
+#[test]
+fn typing_a_duration_sets_the_countdown() {
+    let mut app = app();
+
+    let mut events = vec![key(':')];
+    events.extend("90s".chars().map(key));
+    events.push(code(KeyCode::Enter));
+    run(&mut app, events);
+    assert!(app.input.is_none());
+    assert_eq!(app.duration, Duration::seconds(90));
+    assert!(app.time_left <= Duration::seconds(90));
+
+    run(&mut app, vec![key(':'), key('x'), code(KeyCode::Enter)]);
+    assert!(app.input.is_some());
+    assert!(app.input_error.is_some());
+
+    run(&mut app, vec![code(KeyCode::Backspace), code(KeyCode::Esc)]);
+    assert!(app.input.is_none());
+    assert!(app.running);
+    assert_eq!(app.duration, Duration::seconds(90));
+}
+
+#[test]
+fn d_flips_the_clock_between_time_left_and_elapsed() {
+    let mut app = app();
+
+    run(&mut app, vec![Event::Tick, key(' ')]);
+    app.time_left = Duration::minutes(4);
+    assert_eq!(app.clock_string(), " 00:04:00");
+
+    run(&mut app, vec![key('d'), Event::Tick]);
+    assert_eq!(app.clock_string(), " 00:01:00");
+
+    run(&mut app, vec![key(' ')]);
+    app.end_time = Local::now() - Duration::seconds(3);
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert_eq!(app.clock_string(), " 00:05:03");
+
+    run(&mut app, vec![key('d')]);
+    assert_eq!(app.clock_string(), "+00:00:03 overtime");
+}
+
+#[test]
+fn only_the_last_timer_going_off_counts_as_completed() {
+    let timer = |minutes, is_break| Timer {
+        duration: Duration::minutes(minutes),
+        label: None,
+        sound: None,
+        is_break,
+    };
+    let mut app = App::builder()
+        .timer(timer(5, false))
+        .timer(timer(1, true))
+        .build()
+        .unwrap();
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert!(!app.completed);
+
+    app.dismiss();
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.completed);
+
+    run(&mut app, vec![key('q')]);
+    assert!(!app.running);
+    assert!(app.completed);
+}
+
+#[test]
+fn quitting_a_running_timer_is_not_completed() {
+    let mut app = app();
+
+    run(&mut app, vec![Event::Tick, key('q')]);
+    assert!(!app.running);
+    assert!(!app.completed);
+}
+
+/// Gets an app into one of its states
+type Setup = fn() -> App;
+
+/// Everything a key press can visibly change
+fn snapshot(app: &App) -> impl PartialEq {
+    (
+        app.state,
+        app.running,
+        app.volume,
+        app.muted,
+        app.show_info,
+        app.show_elapsed,
+        app.input.clone(),
+        app.colour,
+        app.end_time,
+        app.duration,
+        app.preset_index,
+    )
+}
+
+#[test]
+fn every_key_that_does_something_is_in_the_footer() {
+    let states: [(&str, Setup); 7] = [
+        ("waiting", || {
+            App::builder()
+                .duration(Duration::minutes(5))
+                .start_time(Local::now() + Duration::hours(1))
+                .build()
+                .unwrap()
+        }),
+        ("standby", || {
+            App::builder()
+                .presets(vec![Duration::minutes(5), Duration::minutes(10)])
+                .build()
+                .unwrap()
+        }),
+        ("running", app),
+        ("paused", || {
+            let mut app = app();
+            run(&mut app, vec![key(' ')]);
+            app
+        }),
+        ("triggered", || {
+            let mut app = app();
+            app.end_time = Local::now();
+            app.tick();
+            app
+        }),
+        ("restart", || {
+            let mut app = app();
+            run(&mut app, vec![key('r')]);
+            app
+        }),
+        ("quitting", || {
+            let mut app = App::builder()
+                .duration(Duration::minutes(5))
+                .confirm_quit(true)
+                .build()
+                .unwrap();
+            run(&mut app, vec![key('q')]);
+            app
+        }),
+    ];
+    let actions = [
+        Action::Pause,
+        Action::Restart,
+        Action::Quit,
+        Action::AddTime,
+        Action::SubtractTime,
+        Action::Lap,
+        Action::VolumeUp,
+        Action::VolumeDown,
+        Action::Mute,
+        Action::Extend,
+        Action::Recolour,
+        Action::Info,
+        Action::Edit,
+        Action::Elapsed,
+    ];
+
+    for (name, build) in states {
+        for action in actions {
+            let mut app = build();
+            let state = app.state;
+            let before = snapshot(&app);
+
+            let pressed = app.keys.key(action);
+            handle_key_events(KeyEvent::new(KeyCode::Char(pressed), KeyModifiers::NONE), &mut app)
+                .unwrap();
+
+            if snapshot(&app) != before {
+                assert!(
+                    handles(state, action),
+                    "{action:?} does something while {name} but isn't in the footer"
+                );
+            }
+        }
+    }
+
+    let fixed = |state| {
+        key_hints(state)
+            .iter()
+            .filter_map(|&(key, _)| match key {
+                HintKey::Fixed(key) => Some(key),
+                HintKey::Bound(_) => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(fixed(State::Quitting), ["y", "n"]);
+    assert_eq!(fixed(State::Standby), ["↑/↓", "1-9", "Enter"]);
+}
@@ -0,0 +1,30 @@
+use cli_timer::logging::{self, MAX_LOG_BYTES};
+use log::LevelFilter;
+use std::{env, fs, process};
+
+#[test]
+fn a_log_that_cant_be_opened_doesnt_stop_the_timer() {
+    let blocker = env::temp_dir().join(format!("cli-timer-log-blocker-{}", process::id()));
+    fs::write(&blocker, "").unwrap();
+
+    logging::init(Some(&blocker.join("cli-timer.log")), LevelFilter::Warn);
+    fs::remove_file(&blocker).unwrap();
+}
+
+#[test]
+fn a_full_log_is_moved_aside() {
+    let dir = env::temp_dir().join(format!("cli-timer-log-rotate-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("cli-timer.log");
+    fs::write(&path, vec![b'x'; usize::try_from(MAX_LOG_BYTES).unwrap() + 1]).unwrap();
+
+    logging::init(Some(&path), LevelFilter::Warn);
+    log::warn!("after rotating");
+    log::logger().flush();
+
+    let old = fs::metadata(dir.join("cli-timer.log.old")).unwrap().len();
+    let log = fs::read_to_string(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(old, MAX_LOG_BYTES + 1);
+    assert!(log.contains("after rotating"), "{log}");
+}
@@ -0,0 +1,82 @@
+use chrono::Duration;
+use cli_timer::app::{App, State};
+use cli_timer::handler::handle_key_events;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::thread;
+
+fn press(app: &mut App, key: char) {
+    handle_key_events(KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE), app).unwrap();
+}
+
+#[test]
+fn totals_time_across_pauses() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .build()
+        .unwrap();
+
+    for _ in 0..3 {
+        press(&mut app, ' ');
+        assert!(app.state == State::Paused);
+        thread::sleep(std::time::Duration::from_millis(50));
+        app.tick();
+
+        press(&mut app, ' ');
+        assert!(app.state == State::Running);
+        app.tick();
+    }
+
+    assert!(app.paused_total >= Duration::milliseconds(150));
+    assert!(app.paused_total < Duration::seconds(1));
+}
+
+#[test]
+fn running_does_not_count_as_paused() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .build()
+        .unwrap();
+
+    app.tick();
+    thread::sleep(std::time::Duration::from_millis(50));
+    app.tick();
+
+    assert_eq!(app.paused_total, Duration::zero());
+}
+
+#[test]
+fn pausing_leaves_the_time_left_exactly_as_it_was() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .build()
+        .unwrap();
+
+    app.tick();
+    press(&mut app, ' ');
+    let frozen = app.time_left;
+    let end_time = app.end_time;
+
+    for _ in 0..5 {
+        thread::sleep(std::time::Duration::from_millis(20));
+        app.tick();
+        assert_eq!(app.time_left, frozen);
+        assert_eq!(app.end_time, end_time);
+    }
+
+    press(&mut app, ' ');
+    assert!(app.state == State::Running);
+    assert!(app.end_time - end_time >= Duration::milliseconds(100));
+}
+
+#[test]
+fn starting_from_now_gives_back_the_time_held() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .build()
+        .unwrap();
+
+    thread::sleep(std::time::Duration::from_millis(100));
+    app.start_from_now();
+    app.tick();
+    assert!(app.time_left > Duration::milliseconds(59_950));
+}
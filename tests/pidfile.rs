@@ -0,0 +1,24 @@
+use cli_timer::pidfile::PidFile;
+use std::{env, fs, process};
+
+#[test]
+fn holds_the_pid_until_dropped() {
+    let path = env::temp_dir().join(format!("cli-timer-{}-held.pid", process::id()));
+
+    let pid_file = PidFile::create(&path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), format!("{}\n", process::id()));
+
+    drop(pid_file);
+    assert!(!path.exists());
+}
+
+#[test]
+fn replaces_a_stale_file_but_not_a_live_one() {
+    let path = env::temp_dir().join(format!("cli-timer-{}-stale.pid", process::id()));
+
+    fs::write(&path, "not a pid\n").unwrap();
+    let pid_file = PidFile::create(&path).unwrap();
+    assert!(PidFile::create(&path).is_err());
+
+    drop(pid_file);
+}
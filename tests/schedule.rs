@@ -0,0 +1,70 @@
+use chrono::Duration;
+use cli_timer::app::{Result, Timer};
+use cli_timer::schedule;
+use std::{env, fs, process};
+
+fn load(name: &str, contents: &str) -> Result<Vec<Timer>> {
+    let path = env::temp_dir().join(format!("cli-timer-schedule-{name}-{}.txt", process::id()));
+    fs::write(&path, contents).unwrap();
+    let timers = schedule::load(&path);
+    fs::remove_file(&path).unwrap();
+    timers
+}
+
+#[test]
+fn reads_duration_label_and_sound() -> Result<()> {
+    let timers = load("fields", "25m, Work, work.mp3\n5m, Break\n00:01:30\n")?;
+
+    assert_eq!(timers.len(), 3);
+    assert_eq!(timers[0].duration, Duration::minutes(25));
+    assert_eq!(timers[0].label.as_deref(), Some("Work"));
+    assert_eq!(timers[0].sound.as_deref(), Some("work.mp3"));
+    assert_eq!(timers[1].duration, Duration::minutes(5));
+    assert_eq!(timers[1].label.as_deref(), Some("Break"));
+    assert_eq!(timers[1].sound, None);
+    assert_eq!(timers[2].duration, Duration::seconds(90));
+    assert_eq!(timers[2].label, None);
+    Ok(())
+}
+
+#[test]
+fn skips_blank_lines_and_comments() -> Result<()> {
+    let timers = load("comments", "# duration, label, sound\n\n   \n25m, Work\n  # 5m, Break\n10m\n")?;
+
+    assert_eq!(timers.len(), 2);
+    assert_eq!(timers[0].duration, Duration::minutes(25));
+    assert_eq!(timers[1].duration, Duration::minutes(10));
+    Ok(())
+}
+
+#[test]
+fn only_the_sound_can_hold_commas() -> Result<()> {
+    let timers = load("commas", "25m ,  Deep work , sounds/bells, soft.wav\n5m,,chime.wav\n5m, ,\n")?;
+
+    assert_eq!(timers[0].label.as_deref(), Some("Deep work"));
+    assert_eq!(timers[0].sound.as_deref(), Some("sounds/bells, soft.wav"));
+    assert_eq!(timers[1].label, None);
+    assert_eq!(timers[1].sound.as_deref(), Some("chime.wav"));
+    assert_eq!(timers[2].label, None);
+    assert_eq!(timers[2].sound, None);
+    Ok(())
+}
+
+#[test]
+fn errors_name_the_line_they_are_on() {
+    let error = load("bad-line", "# warm up\n\n25m, Work\nsoon, Break\n").err().unwrap().to_string();
+    assert!(error.contains("line 4"), "{error}");
+    assert!(error.contains("\"soon\""), "{error}");
+
+    let error = load("bad-first-line", ", Work\n").err().unwrap().to_string();
+    assert!(error.contains("line 1"), "{error}");
+}
+
+#[test]
+fn rejects_a_schedule_with_no_timers() {
+    let error = load("empty", "# nothing yet\n\n").err().unwrap().to_string();
+    assert!(error.contains("no timers"), "{error}");
+
+    let missing = env::temp_dir().join(format!("cli-timer-schedule-missing-{}.txt", process::id()));
+    assert!(schedule::load(&missing).is_err());
+}
@@ -0,0 +1,281 @@
+use chrono::{Duration, Local};
+use cli_timer::app::{App, AudioOutput, BellPattern, Escalation, State};
+use cli_timer::config::QuietHours;
+use std::time::Instant;
+use std::{env, fs, process, thread};
+
+/// This many seconds of silence as 8kHz mono 16-bit PCM
+fn silent_wav(seconds: u32) -> Vec<u8> {
+    let samples = 8000 * seconds;
+    let data_len = samples * 2;
+
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16_u32.to_le_bytes());
+    wav.extend_from_slice(&1_u16.to_le_bytes());
+    wav.extend_from_slice(&1_u16.to_le_bytes());
+    wav.extend_from_slice(&8000_u32.to_le_bytes());
+    wav.extend_from_slice(&16000_u32.to_le_bytes());
+    wav.extend_from_slice(&2_u16.to_le_bytes());
+    wav.extend_from_slice(&16_u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.resize(wav.len() + data_len as usize, 0);
+    wav
+}
+
+#[test]
+fn each_alarm_reads_the_sound_file_again() {
+    let path = env::temp_dir().join(format!("cli-timer-reload-{}.wav", process::id()));
+    fs::write(&path, silent_wav(3)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .sound_start_offset(std::time::Duration::from_secs(2))
+        .reload_sound(true)
+        .build()
+        .unwrap();
+
+    let first = app.start_sound();
+    fs::write(&path, silent_wav(1)).unwrap();
+    let second = app.start_sound();
+    fs::remove_file(&path).unwrap();
+
+    assert!(first.is_ok(), "{first:?}");
+    let error = second.unwrap_err().to_string();
+    assert!(error.contains("only 1s long"), "{error}");
+}
+
+#[test]
+fn restarting_stops_the_sound_thread() {
+    let path = env::temp_dir().join(format!("cli-timer-restart-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    fs::remove_file(&path).unwrap();
+    assert!(app.state == State::Triggered);
+
+    // The alarm loops until it is told to stop
+    let sound_thread = app.sound_thread.take().unwrap();
+    thread::sleep(std::time::Duration::from_millis(200));
+    assert!(!sound_thread.is_finished());
+
+    app.restart();
+    assert!(app.sender.is_none());
+
+    let started = Instant::now();
+    while !sound_thread.is_finished() && started.elapsed().as_secs() < 2 {
+        thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(sound_thread.is_finished());
+    sound_thread.join().unwrap();
+}
+
+#[test]
+fn ticking_starts_once_a_second_passes() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .ticking(true)
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now() + Duration::milliseconds(5500);
+    app.tick();
+    assert!(app.ticker.is_none());
+
+    app.end_time = Local::now() + Duration::milliseconds(4500);
+    app.tick();
+    assert!(app.ticker.is_some());
+    assert_eq!(app.last_tick_second, Some(4));
+}
+
+#[test]
+fn alarm_plays_after_the_sound_file_is_deleted() {
+    let path = env::temp_dir().join(format!("cli-timer-deleted-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+    fs::remove_file(&path).unwrap();
+
+    let played = app.start_sound();
+    assert!(played.is_ok(), "{played:?}");
+    app.restart();
+}
+
+#[test]
+fn quiet_trigger_goes_off_without_playing_anything() {
+    let path = env::temp_dir().join(format!("cli-timer-quiet-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .quiet_trigger(true)
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    fs::remove_file(&path).unwrap();
+
+    assert!(app.state == State::Triggered);
+    assert!(app.sender.is_none());
+    assert!(app.sound_thread.is_none());
+    assert!(app.error.is_none());
+}
+
+#[test]
+fn quiet_trigger_keeps_the_escalation_sound_quiet_too() {
+    let path = env::temp_dir().join(format!("cli-timer-quiet-louder-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .escalate(Escalation {
+            step: 50,
+            interval: std::time::Duration::from_millis(10),
+            sound: Some(path.to_string_lossy().into_owned()),
+        })
+        .quiet_trigger(true)
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    thread::sleep(std::time::Duration::from_millis(30));
+    app.tick();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(app.escalation_level, Some(100));
+    assert!(app.sender.is_none());
+    assert!(app.sound_thread.is_none());
+    assert!(app.error.is_none());
+}
+
+#[test]
+fn a_silent_cycle_stays_silent_when_the_alarm_escalates() {
+    let path = env::temp_dir().join(format!("cli-timer-silent-louder-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .sound_every(2)
+        .escalate(Escalation {
+            step: 50,
+            interval: std::time::Duration::from_millis(10),
+            sound: Some(path.to_string_lossy().into_owned()),
+        })
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.alarm_silenced);
+    thread::sleep(std::time::Duration::from_millis(30));
+    app.tick();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(app.escalation_level, Some(100));
+    assert!(app.sender.is_none());
+    assert!(app.sound_thread.is_none());
+}
+
+#[test]
+fn alarm_duration_silences_the_alarm_before_auto_exit_moves_on() {
+    let path = env::temp_dir().join(format!("cli-timer-alarm-duration-{}.wav", process::id()));
+    fs::write(&path, silent_wav(1)).unwrap();
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound(path.to_string_lossy())
+        .auto_exit(Duration::seconds(10))
+        .alarm_duration(Duration::milliseconds(20))
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.sender.is_some());
+
+    thread::sleep(std::time::Duration::from_millis(30));
+    app.tick();
+    fs::remove_file(&path).unwrap();
+
+    assert!(app.sender.is_none());
+    assert!(app.state == State::Triggered);
+    assert!(app.running);
+}
+
+#[test]
+fn a_silent_cycle_doesnt_ring_the_bell() {
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound("alarm.wav")
+        .sound_every(2)
+        .flash(true)
+        .bell_pattern(BellPattern(vec![true]))
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    app.tick();
+    assert!(app.alarm_silenced);
+    assert_eq!(app.bells_rung, 0);
+
+    app.dismiss();
+    app.end_time = Local::now();
+    app.tick();
+    assert!(!app.alarm_silenced);
+    assert!(app.bells_rung > 0);
+}
+
+#[test]
+fn quiet_hours_at_no_volume_leave_only_the_screen_flashing() {
+    let now = Local::now().time();
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .sound("alarm.wav")
+        .flash(true)
+        .bell_pattern(BellPattern(vec![true]))
+        .quiet_hours(QuietHours {
+            start: now - Duration::hours(1),
+            end: now + Duration::hours(1),
+            volume: 0,
+        })
+        .build()
+        .unwrap();
+    app.audio_output = AudioOutput::Idle;
+
+    app.end_time = Local::now();
+    app.tick();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert!(app.flash_ticks > 0);
+    assert!(app.sender.is_none());
+    assert_eq!(app.bells_rung, 0);
+}
@@ -0,0 +1,46 @@
+#![cfg(unix)]
+
+use chrono::{Duration, Local};
+use cli_timer::app::{App, State};
+use std::os::unix::fs::PermissionsExt;
+use std::time::Instant;
+use std::{env, fs, process, thread};
+
+#[test]
+fn reads_the_label_aloud_when_the_timer_goes_off() {
+    let dir = env::temp_dir().join(format!("cli-timer-speech-{}", process::id()));
+    let spoken = dir.join("spoken");
+    fs::create_dir_all(&dir).unwrap();
+
+    // Stands in for whichever program would be found on this platform
+    for engine in ["say", "spd-say"] {
+        let path = dir.join(engine);
+        let script = format!(
+            "#!/bin/sh\nprintf '%s|%s\\n' \"$1\" \"$2\" > {0}.part && /bin/mv {0}.part {0}\n",
+            spoken.display()
+        );
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    env::set_var("PATH", &dir);
+
+    let mut app = App::builder()
+        .duration(Duration::minutes(1))
+        .label("-5 min break")
+        .speak(true)
+        .build()
+        .unwrap();
+    app.end_time = Local::now();
+    app.tick();
+    assert!(app.state == State::Triggered);
+    assert!(app.error.is_none(), "{:?}", app.error);
+
+    let started = Instant::now();
+    while !spoken.exists() && started.elapsed() < std::time::Duration::from_secs(5) {
+        thread::sleep(std::time::Duration::from_millis(20));
+    }
+    let text = fs::read_to_string(&spoken).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(text, "--|-5 min break\n");
+}
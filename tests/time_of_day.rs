@@ -0,0 +1,104 @@
+use chrono::{
+    DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use cli_timer::app::next_occurrence;
+
+/// A zone an hour ahead of UTC between the clocks going forward at 01:00 UTC on 26 March 2023
+/// and back at 01:00 UTC on 29 October 2023, like the UK
+#[derive(Clone, Copy, Debug)]
+struct Summertime;
+
+impl Summertime {
+    fn forward() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 3, 26).unwrap().and_hms_opt(1, 0, 0).unwrap()
+    }
+
+    fn back() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 10, 29).unwrap().and_hms_opt(1, 0, 0).unwrap()
+    }
+
+    fn hours(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+}
+
+impl TimeZone for Summertime {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(12, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        let fits =
+            |offset: &FixedOffset| self.offset_from_utc_datetime(&(*local - *offset)) == *offset;
+        let offsets: Vec<_> = [Self::hours(0), Self::hours(1)].into_iter().filter(fits).collect();
+
+        match offsets[..] {
+            [offset] => LocalResult::Single(offset),
+            // Summer time comes first when the clocks go back
+            [winter, summer] => LocalResult::Ambiguous(summer, winter),
+            _ => LocalResult::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        self.offset_from_utc_datetime(&utc.and_hms_opt(12, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        if *utc >= Self::forward() && *utc < Self::back() {
+            Self::hours(1)
+        } else {
+            Self::hours(0)
+        }
+    }
+}
+
+fn local(day: &str) -> DateTime<Summertime> {
+    let local = NaiveDateTime::parse_from_str(day, "%Y-%m-%d %H:%M").unwrap();
+    Summertime.from_local_datetime(&local).earliest().unwrap()
+}
+
+fn time(time: &str) -> NaiveTime {
+    NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+}
+
+fn utc(at: &str) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M").unwrap())
+}
+
+#[test]
+fn rolls_over_to_tomorrow_past_midnight() {
+    let next = next_occurrence(time("00:30"), &local("2023-06-01 23:50")).unwrap();
+    assert_eq!(next.with_timezone(&Utc), utc("2023-06-01 23:30"));
+
+    let next = next_occurrence(time("23:55"), &local("2023-06-01 23:50")).unwrap();
+    assert_eq!(next.with_timezone(&Utc), utc("2023-06-01 22:55"));
+}
+
+#[test]
+fn counts_the_hour_lost_when_the_clocks_go_forward() {
+    // Only 7 hours pass overnight from 23:00 to 07:00
+    let next = next_occurrence(time("07:00"), &local("2023-03-25 23:00")).unwrap();
+    assert_eq!(next.with_timezone(&Utc), utc("2023-03-26 06:00"));
+    assert_eq!((next - local("2023-03-25 23:00")).num_hours(), 7);
+
+    // 01:30 never happens, so it falls when it would have without the change
+    let next = next_occurrence(time("01:30"), &local("2023-03-26 00:45")).unwrap();
+    assert_eq!(next.with_timezone(&Utc), utc("2023-03-26 01:30"));
+}
+
+#[test]
+fn takes_the_next_reading_of_a_repeated_time() {
+    let first = next_occurrence(time("01:30"), &local("2023-10-29 01:10")).unwrap();
+    assert_eq!(first.with_timezone(&Utc), utc("2023-10-29 00:30"));
+
+    // Already past the first 01:30, so the clock reads it again an hour later
+    let second = next_occurrence(time("01:30"), &first).unwrap();
+    assert_eq!(second.with_timezone(&Utc), utc("2023-10-29 01:30"));
+}